@@ -5,12 +5,52 @@ pub const LOWER_POOL_SEED: &str = "lower_pool";
 #[constant]
 pub const HIGHER_POOL_SEED: &str = "higher_pool";
 #[constant]
+pub const LP_MINT_SEED: &str = "lp_mint";
+#[constant]
+pub const CONFIG_SEED: &str = "config";
+#[constant]
+pub const PROTOCOL_FEE_VAULT_SEED: &str = "protocol_fee_vault";
+#[constant]
+pub const CREATOR_FEE_VAULT_SEED: &str = "creator_fee_vault";
+#[constant]
+pub const REFERRER_SEED: &str = "referrer";
+#[constant]
+pub const REFERRAL_VAULT_SEED: &str = "referral_vault";
+#[constant]
+pub const MANUAL_ORACLE_SEED: &str = "manual_oracle";
+#[constant]
+pub const FEED_REGISTRY_SEED: &str = "feed_registry";
+#[constant]
+pub const MAX_FEED_SYMBOL_LEN: usize = 16;
+#[constant]
+pub const RESOLUTION_SEED: &str = "resolution";
+#[constant]
+pub const RESOLUTION_VAULT_SEED: &str = "resolution_vault";
+#[constant]
+pub const COLLATERAL_CONFIG_SEED: &str = "collateral_config";
+#[constant]
+pub const CREATOR_REGISTRY_SEED: &str = "creator_registry";
+#[constant]
+pub const MAX_CREATOR_FEEDS: usize = 8;
+#[constant]
+pub const BOND_VAULT_SEED: &str = "bond_vault";
+#[constant]
+pub const UNWRAP_SEED: &str = "unwrap";
+#[constant]
 pub const BET_SEED: &str = "prediction_bet";
 #[constant]
-pub const MARKET_LOCK_PERIOD: u64 = 576000; //more than two days
+pub const ESTIMATED_SLOT_MS: u64 = 400; //only used to date the start of migrated legacy markets
+#[constant]
+pub const LEGACY_POOL_SEED_AMOUNT: u64 = 1_000_000; //seeded into each pool by the program before the upgrade
+#[constant]
+pub const MAX_TRUSTED_MINTS: usize = 8;
 #[constant]
-pub const USDC_MINT: &str = "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU";
+pub const MAX_EXTRA_ORACLE_FEEDS: usize = 2;
 #[constant]
-pub const INITIAL_USDC_POOL_AMOUNT: u64 = 1000000;
+pub const MIN_TARGET_EXPONENT: i32 = -18;
+#[constant]
+pub const MAX_TARGET_EXPONENT: i32 = 18;
 #[constant]
 pub const ODDS_FIXED_POINT_MULTIPLIER: u64 = 1_000_000;
+#[constant]
+pub const BPS_DENOMINATOR: u64 = 10_000;
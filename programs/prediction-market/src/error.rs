@@ -4,7 +4,7 @@ use anchor_lang::prelude::*;
 pub enum MarketError {
     #[msg("Pyth Solana Feed ID is expected to have 66 characters")]
     IncorrectFeedIDLength,
-    #[msg("Market Duration Can not be less than 1200 slots")]
+    #[msg("Market duration is below the minimum of the config")]
     ShortMarketDuration,
     #[msg("Only the account creator can change account state")]
     UnauthorizedUser,
@@ -16,11 +16,11 @@ pub enum MarketError {
     InvalidMarketInitialization,
     #[msg("Market betting Duration is not over yet")]
     MarketDurationNotOver,
-    #[msg("576000 slots must pass after Market betting period before closing market")]
+    #[msg("The lock period of the config must pass after the betting period before closing market")]
     MarketLockPeriodNotOver,
     #[msg("Bet Can only be placed during the market duration")]
     MarketDurationOver,
-    #[msg("Bet pools are non zero. Market can not cancel")]
+    #[msg("Market has unclaimed bets or liquidity and can not close")]
     NonZeroPools,
     #[msg("Error during UTF8 conversion")]
     InvalidUtf8,
@@ -32,4 +32,136 @@ pub enum MarketError {
     InvalidFeedId,
     #[msg("Overflow occured when adjusting the oracle price data")]
     PriceAdjustmentOverflow,
+    #[msg("Arithmetic overflow in fixed-point math")]
+    MathOverflow,
+    #[msg("Division by zero in fixed-point math")]
+    MathDivisionByZero,
+    #[msg("Fee basis points can not exceed 10000")]
+    InvalidFeeBps,
+    #[msg("Liquidity amount must be greater than zero")]
+    ZeroLiquidity,
+    #[msg("Market has not been settled yet")]
+    MarketNotSettled,
+    #[msg("Market is already settled")]
+    MarketAlreadySettled,
+    #[msg("Creator fee exceeds the maximum allowed by the protocol config")]
+    CreatorFeeTooHigh,
+    #[msg("Bet amount does not cover the fees")]
+    BetTooSmallForFees,
+    #[msg("Fee curve must have fee_min_bps <= fee_max_bps and ramp_bps <= 10000")]
+    InvalidFeeCurve,
+    #[msg("A bettor can not refer their own bet")]
+    SelfReferral,
+    #[msg("Referrer stats and referral vault must be passed together")]
+    IncompleteReferralAccounts,
+    #[msg("No referral rewards to claim")]
+    NoReferralRewards,
+    #[msg("Price account is not owned by the oracle program of the market")]
+    InvalidOracleAccount,
+    #[msg("Oracle price is older than the allowed age")]
+    StalePrice,
+    #[msg("Oracle account does not publish the market feed")]
+    OracleFeedMismatch,
+    #[msg("Oracle has not published a valid price")]
+    InvalidOraclePrice,
+    #[msg("Oracle confidence interval is wider than the market allows")]
+    PriceConfidenceTooWide,
+    #[msg("Confidence limit must be between 1 and 10000 basis points")]
+    InvalidConfidenceLimit,
+    #[msg("Settlement window of the market is over")]
+    SettlementWindowOver,
+    #[msg("Settlement window of the market is not over")]
+    SettlementWindowNotOver,
+    #[msg("Market is voided")]
+    MarketVoided,
+    #[msg("Oracle price must be greater than zero")]
+    NonPositivePrice,
+    #[msg("Target exponent is out of the supported range")]
+    InvalidTargetExponent,
+    #[msg("Oracle staleness and settlement window limits must be greater than zero")]
+    InvalidOracleLimits,
+    #[msg("Maximum price age must be between 1 second and the protocol limit")]
+    InvalidMaxPriceAge,
+    #[msg("Settlement window must be between 1 slot and the protocol limit")]
+    InvalidSettlementWindow,
+    #[msg("Pyth price update does not meet the verification level required by the market")]
+    InsufficientPriceVerification,
+    #[msg("Partially verified markets must require at least one signature")]
+    InvalidMinSignatures,
+    #[msg("Account is not a market in the legacy 66-byte feed id layout")]
+    InvalidLegacyMarket,
+    #[msg("Feed symbol must be between 1 and 16 bytes")]
+    InvalidFeedSymbol,
+    #[msg("Feed is disabled in the feed registry")]
+    FeedDisabled,
+    #[msg("Bond and challenge window of optimistic resolution must be greater than zero and within the protocol limit")]
+    InvalidOptimisticResolution,
+    #[msg("Market does not allow optimistic resolution")]
+    OptimisticResolutionDisabled,
+    #[msg("An optimistic resolution of the market is pending")]
+    ResolutionPending,
+    #[msg("Resolution is not in the expected status")]
+    InvalidResolutionStatus,
+    #[msg("Challenge window of the resolution is over")]
+    ChallengeWindowOver,
+    #[msg("Challenge window of the resolution is not over")]
+    ChallengeWindowNotOver,
+    #[msg("Instruction is not supported for this kind of market")]
+    InvalidMarketKind,
+    #[msg("Resolution deadline of the event market is over")]
+    ResolutionDeadlineOver,
+    #[msg("Extra feeds must be at most two distinct registered feeds with an agreement threshold they can meet")]
+    InvalidOracleAgreement,
+    #[msg("Price accounts of the extra oracle feeds are missing")]
+    IncompleteOracleAccounts,
+    #[msg("Not enough valid oracle readings agree with the median price")]
+    InsufficientOracleAgreement,
+    #[msg("Mint has a permanent delegate or transfer hook and is not trusted by the protocol")]
+    UnsupportedMintExtension,
+    #[msg("Too many trusted mints")]
+    TooManyTrustedMints,
+    #[msg("Minimum bet must be positive and not above the maximum bet")]
+    InvalidBetLimits,
+    #[msg("Mint decimals differ from the collateral config")]
+    CollateralDecimalsMismatch,
+    #[msg("Bet amount is outside the allowed range")]
+    BetOutOfRange,
+    #[msg("A token account is required unless the market collateral is wrapped SOL")]
+    MissingUserTokenAccount,
+    #[msg("This action is paused")]
+    ActionPaused,
+    #[msg("Market creation requires a registered creator")]
+    CreatorNotRegistered,
+    #[msg("Creator has reached its quota of open markets")]
+    CreatorQuotaReached,
+    #[msg("Creator is not allowed to create markets on this feed or on events")]
+    FeedNotAllowedForCreator,
+    #[msg("Too many allowed feeds for a creator")]
+    TooManyCreatorFeeds,
+    #[msg("Creator still has open markets")]
+    CreatorHasOpenMarkets,
+    #[msg("Market was resolved or its bond can not be slashed yet")]
+    BondNotSlashable,
+    #[msg("The bond vault of the market is required")]
+    MissingBondVault,
+    #[msg("Liquidity providers are underwater, no deposits are accepted")]
+    PoolsUnderwater,
+    #[msg("Price was not published within the maximum price age after expiry")]
+    PriceOutsideSettlementWindow,
+    #[msg("Legacy markets with pools need their lp mint and creator fee vault created")]
+    MissingLegacyMarketVaults,
+    #[msg("Account is not a bet in the legacy layout")]
+    InvalidLegacyBet,
+    #[msg("Pool seed amount must be greater than zero")]
+    InvalidPoolSeedAmount,
+    #[msg("Rent must be returned to the account that paid it")]
+    InvalidRentPayer,
+    #[msg("Market was voided without a price, its bond must be slashed before it closes")]
+    BondPendingSlash,
+    #[msg("The first liquidity deposit must come before any bet")]
+    FirstDepositAfterBets,
+    #[msg("Arbitration window must be greater than zero")]
+    InvalidArbitrationWindow,
+    #[msg("The arbiter can still decide the dispute")]
+    ArbitrationWindowNotOver,
 }
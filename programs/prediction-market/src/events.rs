@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{Direction, PauseFlags};
+
+#[event]
+pub struct BetPlaced {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub direction: Direction,
+    pub amount: u64,
+    pub stake: u64,
+    pub odds: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub lp_fee: u64,
+    pub dynamic_fee_bps: u16,
+    pub dynamic_fee: u64,
+    pub referrer: Option<Pubkey>,
+    pub referral_fee: u64,
+}
+
+#[event]
+pub struct MarketVoided {
+    pub market: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct BetRefunded {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BetClaimed {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub is_won: bool,
+    pub payout: u64,
+}
+
+#[event]
+pub struct ProtocolFeesWithdrawn {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CreatorFeesWithdrawn {
+    pub market: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReferralRewardsClaimed {
+    pub referrer: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ResolutionProposed {
+    pub market: Pubkey,
+    pub proposer: Pubkey,
+    pub price: u64,
+    pub bond: u64,
+}
+
+#[event]
+pub struct ResolutionDisputed {
+    pub market: Pubkey,
+    pub disputer: Pubkey,
+}
+
+#[event]
+pub struct ResolutionFinalized {
+    pub market: Pubkey,
+    pub price: u64,
+    pub disputed: bool,
+    pub bond_recipient: Pubkey,
+    pub payout: u64,
+}
+
+#[event]
+pub struct ResolutionExpired {
+    pub market: Pubkey,
+    pub proposer: Pubkey,
+    pub disputer: Pubkey,
+}
+
+#[event]
+pub struct EventResolved {
+    pub market: Pubkey,
+    pub resolver: Pubkey,
+    pub outcome: bool,
+}
+
+#[event]
+pub struct AdminProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub previous_admin: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct PauseChanged {
+    pub market: Option<Pubkey>, // None for the protocol-wide flags
+    pub authority: Pubkey,
+    pub pause: PauseFlags,
+}
+
+#[event]
+pub struct CreatorBondSlashed {
+    pub market: Pubkey,
+    pub creator: Pubkey,
+    pub slasher: Pubkey,
+    pub amount: u64, // moved into the protocol fee vault
+}
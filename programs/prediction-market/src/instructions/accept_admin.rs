@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::events::AdminTransferred;
+use crate::states::*;
+use crate::MarketError;
+
+//second step of an admin transfer, signed by the proposed admin
+pub fn _accept_admin(
+    ctx: Context<AcceptAdmin>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let previous_admin = config.admin;
+
+    config.admin = ctx.accounts.pending_admin.key();
+    config.pending_admin = None;
+
+    emit!(AdminTransferred {
+        previous_admin,
+        admin: config.admin,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        constraint = config.pending_admin == Some(pending_admin.key()) @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub pending_admin: Signer<'info>,
+}
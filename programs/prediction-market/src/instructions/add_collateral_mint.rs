@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+
+//allows `mint` as market collateral with the given bet limits
+pub fn _add_collateral_mint(
+    ctx: Context<AddCollateralMint>,
+    params: CollateralParams,
+) -> Result<()> {
+    let collateral_config = &mut ctx.accounts.collateral_config;
+
+    collateral_config.mint = ctx.accounts.mint.key();
+    collateral_config.decimals = ctx.accounts.mint.decimals;
+    collateral_config.bump = ctx.bumps.collateral_config;
+    collateral_config.rent_payer = ctx.accounts.payer.key();
+    collateral_config.apply(&params)
+}
+
+#[derive(Accounts)]
+pub struct AddCollateralMint<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CollateralConfig::INIT_SPACE,
+        seeds = [
+            COLLATERAL_CONFIG_SEED.as_bytes(),
+            mint.key().as_ref(),
+        ],
+        bump
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    //may be a PDA of a multisig program, rent is paid by a separate signer
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
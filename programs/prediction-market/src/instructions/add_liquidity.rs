@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::math;
+use crate::states::*;
+use crate::utils::received_amount;
+use crate::MarketError;
+
+//deposits `amount` into each pool and mints LP tokens priced at the surplus the pools already
+//hold for their LPs, the first deposit mints one token per unit each pool received. The first
+//deposit must come before any bet, the stakes and fees of earlier bets belong to the creator
+pub fn _add_liquidity(
+    ctx: Context<AddLiquidity>,
+    amount: u64,
+) -> Result<()> {
+    let market = &ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    require!(market.initialization == MarketInitialization::InitializedPools, MarketError::InvalidMarketInitialization);
+    require!(!ctx.accounts.config.pause.betting && !market.pause.betting, MarketError::ActionPaused);
+    require_gte!(market.expiry()?, clock.slot, MarketError::MarketDurationOver);
+    require_gt!(amount, 0, MarketError::ZeroLiquidity);
+
+    let higher_balance = ctx.accounts.higher_pool.amount;
+    let lower_balance = ctx.accounts.lower_pool.amount;
+    let lp_supply = ctx.accounts.lp_mint.supply;
+    let surplus = market.lp_surplus(higher_balance, lower_balance)?;
+    if lp_supply == 0 {
+        //pools holding more than the seeds took bets, a first deposit could withdraw their stakes
+        let seeds = math::checked_add(market.seed_amount, market.seed_amount)?;
+        require_gte!(seeds, math::checked_add(higher_balance, lower_balance)?, MarketError::FirstDepositAfterBets);
+    }
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.provider_ata.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.higher_pool.to_account_info(),
+                authority: ctx.accounts.provider.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.provider_ata.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.lower_pool.to_account_info(),
+                authority: ctx.accounts.provider.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    let higher_received = received_amount(&mut ctx.accounts.higher_pool, higher_balance)?;
+    let lower_received = received_amount(&mut ctx.accounts.lower_pool, lower_balance)?;
+    //a transfer fee can leave the pools with less than `amount`
+    let lp_amount = if lp_supply == 0 {
+        higher_received.min(lower_received)
+    } else {
+        math::lp_tokens(math::checked_add(higher_received, lower_received)?, lp_supply, surplus)?
+    };
+    require_gt!(lp_amount, 0, MarketError::ZeroLiquidity);
+
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                to: ctx.accounts.provider_lp_ata.to_account_info(),
+                authority: ctx.accounts.market.to_account_info(),
+            },
+            &[&[
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+            &[market.bump],
+        ]],
+        ),
+        lp_amount,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    #[account(
+        seeds = [
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        seeds = [
+            HIGHER_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_pool_bump,
+    )]
+    pub higher_pool: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        seeds = [
+            LOWER_POOL_SEED.as_bytes(),
+            market.key().as_ref()
+        ],
+        bump = market.lower_pool_bump,
+    )]
+    pub lower_pool: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            LP_MINT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.lp_mint_bump,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program,
+    )]
+    pub provider_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = provider,
+        associated_token::mint = lp_mint,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program,
+    )]
+    pub provider_lp_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub provider: Signer<'info>,
+
+    #[account(address = market.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
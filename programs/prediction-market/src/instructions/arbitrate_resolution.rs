@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::events::ResolutionFinalized;
+use crate::instructions::{release_resolution_vault, apply_resolution};
+use crate::states::*;
+use crate::MarketError;
+
+//the arbiter settles a disputed proposal, whoever was right receives both bonds. The
+//rent of the resolution goes back to the proposer
+pub fn _arbitrate_resolution(
+    ctx: Context<ArbitrateResolution>,
+    price: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let resolution = &mut ctx.accounts.resolution;
+
+    require!(resolution.status == ResolutionStatus::Disputed,MarketError::InvalidResolutionStatus);
+    require!(!ctx.accounts.config.pause.claiming && !market.pause.claiming,MarketError::ActionPaused);
+
+    let bond_recipient = if price == resolution.proposed_price {
+        resolution.proposer
+    } else {
+        resolution.disputer.ok_or(MarketError::InvalidResolutionStatus)?
+    };
+    require_keys_eq!(ctx.accounts.bond_recipient_ata.owner,bond_recipient,MarketError::UnauthorizedUser);
+
+    apply_resolution(market, resolution, price);
+
+    let payout = ctx.accounts.resolution_vault.amount;
+    release_resolution_vault(
+        market,
+        &ctx.accounts.token_program,
+        &ctx.accounts.resolution_vault,
+        &ctx.accounts.mint,
+        &ctx.accounts.bond_recipient_ata,
+        &ctx.accounts.proposer,
+    )?;
+
+    emit!(ResolutionFinalized {
+        market: market.key(),
+        price,
+        disputed: true,
+        bond_recipient,
+        payout,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ArbitrateResolution<'info> {
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = arbiter @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        has_one = proposer,
+        close = proposer,
+        seeds = [
+            RESOLUTION_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = resolution.bump,
+    )]
+    pub resolution: Box<Account<'info, Resolution>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RESOLUTION_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = resolution.vault_bump,
+    )]
+    pub resolution_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+    )]
+    pub bond_recipient_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+
+    pub arbiter: Signer<'info>,
+
+    #[account(address = market.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
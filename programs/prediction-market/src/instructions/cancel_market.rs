@@ -1,10 +1,10 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{close_account, CloseAccount,transfer,Transfer, Token, TokenAccount};
+use anchor_spl::token_interface::*;
 
 use crate::constants::*;
+use crate::utils::release_bond_vault;
 use crate::states::*;
 use crate::MarketError;
-use crate::utils::hash_to_bytes;
 
 
 pub fn _cancel_market(
@@ -15,45 +15,50 @@ pub fn _cancel_market(
     let lower_pool = &ctx.accounts.lower_pool;
     let creator = &ctx.accounts.market_creator;
 
-    require_eq!(higher_pool.amount + lower_pool.amount,INITIAL_USDC_POOL_AMOUNT*2,MarketError::NonZeroPools);
+    require_eq!(higher_pool.amount + lower_pool.amount,market.seed_amount*2,MarketError::NonZeroPools);
     require_keys_eq!(creator.key(),market.creator,MarketError::UnauthorizedUser);
+    require!(!(market.voided_by_timeout && market.bond_vault_open),MarketError::BondPendingSlash);
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: higher_pool.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.creator_ata.to_account_info(),
                 authority: ctx.accounts.market.to_account_info(),
             },
             &[&[
             market.creator.key().as_ref(), 
-            &hash_to_bytes(&market.feed_id),
+            &market.feed_seed,
             &market.target_price.to_le_bytes(), 
             &market.market_duration.to_le_bytes(),
             &[ctx.accounts.market.bump],
         ]],
         ),
-        INITIAL_USDC_POOL_AMOUNT,
+        market.seed_amount,
+        ctx.accounts.mint.decimals,
     )?;
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: lower_pool.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.creator_ata.to_account_info(),
                 authority: ctx.accounts.market.to_account_info(),
             },
             &[&[
             market.creator.key().as_ref(), 
-            &hash_to_bytes(&market.feed_id),
+            &market.feed_seed,
             &market.target_price.to_le_bytes(), 
             &market.market_duration.to_le_bytes(),
             &[ctx.accounts.market.bump],
         ]],
         ),
-        INITIAL_USDC_POOL_AMOUNT,
+        market.seed_amount,
+        ctx.accounts.mint.decimals,
     )?;
 
     close_account(CpiContext::new_with_signer(
@@ -65,7 +70,7 @@ pub fn _cancel_market(
         }, 
         &[&[
             market.creator.key().as_ref(), 
-            &hash_to_bytes(&market.feed_id),
+            &market.feed_seed,
             &market.target_price.to_le_bytes(), 
             &market.market_duration.to_le_bytes(),
             &[ctx.accounts.market.bump],
@@ -81,13 +86,77 @@ pub fn _cancel_market(
         }, 
         &[&[
             market.creator.key().as_ref(), 
-            &hash_to_bytes(&market.feed_id),
+            &market.feed_seed,
             &market.target_price.to_le_bytes(), 
             &market.market_duration.to_le_bytes(),
             &[ctx.accounts.market.bump],
         ]],
     ))?;
 
+    //uncollected creator fees are swept to the creator before the vault is closed
+    let creator_fee_amount = ctx.accounts.creator_fee_vault.amount;
+    if creator_fee_amount > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.creator_fee_vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.creator_ata.to_account_info(),
+                    authority: ctx.accounts.market.to_account_info(),
+                },
+                &[&[
+                market.creator.key().as_ref(), 
+                &market.feed_seed,
+                &market.target_price.to_le_bytes(), 
+                &market.market_duration.to_le_bytes(),
+                &[ctx.accounts.market.bump],
+            ]],
+            ),
+            creator_fee_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(), 
+        CloseAccount{ 
+            account: ctx.accounts.creator_fee_vault.to_account_info(), 
+            destination: ctx.accounts.market_creator.to_account_info(), 
+            authority: ctx.accounts.market.to_account_info()
+        }, 
+        &[&[
+            market.creator.key().as_ref(), 
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+            &[ctx.accounts.market.bump],
+        ]],
+    ))?;
+
+    //a market closed by its creator gets the bond back
+    if market.bond_vault_open {
+        let bond_vault = ctx.accounts.bond_vault.as_ref().ok_or(MarketError::MissingBondVault)?;
+        release_bond_vault(
+            market,
+            &ctx.accounts.token_program,
+            bond_vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.creator_ata,
+            &ctx.accounts.market_creator.to_account_info(),
+        )?;
+    }
+
+    //frees the slot of the market in the quota of its creator
+    if market.counted_by_registry {
+        let creator_registry = ctx
+            .accounts
+            .creator_registry
+            .as_mut()
+            .ok_or(MarketError::CreatorNotRegistered)?;
+        creator_registry.open_markets = creator_registry.open_markets.saturating_sub(1);
+    }
+
     Ok(())
 }
 
@@ -98,7 +167,7 @@ pub struct CancelMarket<'info> {
         close = market_creator,
         seeds = [
             market.creator.key().as_ref(), 
-            &hash_to_bytes(&market.feed_id),
+            &market.feed_seed,
             &market.target_price.to_le_bytes(), 
             &market.market_duration.to_le_bytes(),
         ],
@@ -108,7 +177,6 @@ pub struct CancelMarket<'info> {
 
     #[account(
         mut,
-        close = market_creator,
         token::mint = market.mint, 
         token::authority = market,
         seeds = [
@@ -117,11 +185,10 @@ pub struct CancelMarket<'info> {
         ],
         bump = market.higher_pool_bump,
     )]
-    pub higher_pool: Account<'info, TokenAccount>,
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        close = market_creator,
         token::mint = market.mint, 
         token::authority = market,
         seeds = [
@@ -130,14 +197,27 @@ pub struct CancelMarket<'info> {
         ],
         bump = market.lower_pool_bump,
     )]
-    pub lower_pool: Account<'info, TokenAccount>,
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         associated_token::mint = market.mint,
         associated_token::authority = market_creator,
+        associated_token::token_program = token_program,
     )]
-    pub creator_ata: Account<'info, TokenAccount>,
+    pub creator_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.mint, 
+        token::authority = market,
+        seeds = [
+            CREATOR_FEE_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.creator_fee_vault_bump,
+    )]
+    pub creator_fee_vault: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
@@ -145,7 +225,32 @@ pub struct CancelMarket<'info> {
     )]
     pub market_creator: Signer<'info>,
 
+    #[account(address = market.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            CREATOR_REGISTRY_SEED.as_bytes(),
+            market.creator.as_ref(),
+        ],
+        bump = creator_registry.bump,
+    )]
+    pub creator_registry: Option<Account<'info, CreatorRegistry>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        seeds = [
+            BOND_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.bond_vault_bump,
+    )]
+    pub bond_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 
 }
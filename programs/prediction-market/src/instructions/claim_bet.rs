@@ -1,16 +1,21 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::*;
+use anchor_lang::system_program::{allocate, assign, transfer, Allocate, Assign, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token_interface::*;
 use num_traits::*;
-use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex,PriceUpdateV2};
 
 use crate::constants::*;
+use crate::events::{BetClaimed, BetRefunded, MarketVoided};
+use crate::math;
 use crate::states::*;
 use crate::MarketError;
-use crate::utils::hash_to_bytes;
+use crate::oracle::settle_final_price;
 
 
-pub fn _claim_bet(
-    ctx: Context<ClaimBet>,
+//price accounts of the extra feeds of the market are passed as remaining accounts
+pub fn _claim_bet<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimBet<'info>>,
 ) -> Result<()> {
     let bet = &mut ctx.accounts.bet;
     let market = &mut ctx.accounts.market;
@@ -18,68 +23,188 @@ pub fn _claim_bet(
     let clock = Clock::get()?;
 
     require!(market.initialization == MarketInitialization::InitializedPools,MarketError::InvalidMarketInitialization);
+    require!(!ctx.accounts.config.pause.claiming && !market.pause.claiming, MarketError::ActionPaused);
     require_keys_eq!(market.key(),bet.market,MarketError::BetMarketMismatch);
     require_keys_eq!(ctx.accounts.user.key(),bet.user,MarketError::UnauthorizedUser);
-    require_gt!(clock.slot,market.start_time + market.market_duration,MarketError::MarketDurationNotOver);
+    require_gt!(clock.slot,market.expiry()?,MarketError::MarketDurationNotOver);
     require_eq!(bet.claimed,false,MarketError::BetIsClaimed);
 
 
-    if market.final_price.is_none() {
-        let feed_id_str = std::str::from_utf8(&market.feed_id)
-            .map_err(|_| MarketError::InvalidUtf8)?;
+    if !market.is_settled() && !market.voided {
+        if clock.slot > market.settlement_deadline(&ctx.accounts.config, clock.slot) && !market.resolution_pending {
+            //nobody settled the market in time, it is voided and every bet refunded
+            market.voided = true;
+            market.voided_by_timeout = true;
 
-        let feed_id = get_feed_id_from_hex(feed_id_str)
-            .map_err(|_| MarketError::InvalidFeedId)?;
-
-        let price =price_update.get_price_no_older_than(
-            &clock,
-            30_u64,
-            &feed_id 
-        )?;
-
-        let adjusted_price = if price.exponent < 0 {
-            (price.price as u64).checked_mul(10_u64.pow(price.exponent.abs() as u32))
-                .ok_or(MarketError::PriceAdjustmentOverflow)?
+            emit!(MarketVoided {
+                market: market.key(),
+                slot: clock.slot,
+            });
         } else {
-            (price.price as u64).checked_div(10_u64.pow(price.exponent as u32))
-                .ok_or(MarketError::PriceAdjustmentOverflow)?
-        };
-
-        market.final_price = Some(adjusted_price);
+            require!(market.kind == MarketKind::Price,MarketError::MarketNotSettled);
+            settle_final_price(market, &price_update.to_account_info(), ctx.remaining_accounts, &clock)?;
+        }
     }
 
     require!(
-        market.final_price.is_some(),
+        market.is_settled() || market.voided,
         MarketError::NoneFinalPrice
     );
 
-    bet.is_won = match bet.direction {
-        Direction::Higher => market.final_price.unwrap() > market.target_price,
-        Direction::Lower => market.final_price.unwrap() < market.target_price,
+    let payout = math::payout(bet.stake, bet.odds)?;
+
+    match bet.direction {
+        Direction::Higher => {
+            market.higher_liability = market.higher_liability.saturating_sub(payout);
+            market.higher_stake = market.higher_stake.saturating_sub(bet.stake);
+        }
+        Direction::Lower => {
+            market.lower_liability = market.lower_liability.saturating_sub(payout);
+            market.lower_stake = market.lower_stake.saturating_sub(bet.stake);
+        }
+    }
+
+    bet.is_won = market.winning_direction() == Some(bet.direction.clone());
+
+    //a voided market refunds the stake of every bet instead of paying the winners
+    let amount = if market.voided {
+        bet.stake
+    } else if bet.is_won {
+        payout
+    } else {
+        0
     };
-    
-    if bet.is_won {
+
+    if amount > 0 {
         let bet_pool: AccountInfo = match bet.direction {
             Direction::Higher => ctx.accounts.higher_pool.to_account_info(),
             Direction::Lower => ctx.accounts.lower_pool.to_account_info(),
         };
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            market.creator.as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+            &[market.bump],
+        ]];
 
-        let payout = bet.amount.checked_mul(bet.odds).unwrap() / ODDS_FIXED_POINT_MULTIPLIER;
-    
-        transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: bet_pool,
-                    to: ctx.accounts.user_ata.to_account_info(),
-                    authority: ctx.accounts.market.to_account_info(),
-                },
-            ),
-            payout,
-        )?;
+        match &ctx.accounts.user_ata {
+            Some(user_ata) => transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: bet_pool,
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: user_ata.to_account_info(),
+                        authority: market.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                ctx.accounts.mint.decimals,
+            )?,
+            None => {
+                //wrapped SOL is moved into a temporary token account that is closed to the user,
+                //which unwraps it together with the rent the user paid for the account
+                require_keys_eq!(ctx.accounts.mint.key(), native_mint::ID, MarketError::MissingUserTokenAccount);
+                let unwrap_account = ctx
+                    .accounts
+                    .unwrap_account
+                    .as_ref()
+                    .ok_or(MarketError::MissingUserTokenAccount)?;
+                let unwrap_bump = ctx.bumps.unwrap_account.ok_or(MarketError::MissingUserTokenAccount)?;
+                let bet_key = bet.key();
+                let unwrap_seeds: &[&[&[u8]]] = &[&[UNWRAP_SEED.as_bytes(), bet_key.as_ref(), &[unwrap_bump]]];
+
+                //create_account fails on an address that already holds lamports, so anyone could block
+                //the claim by funding it. The rent is topped up instead and the space assigned by the PDA
+                let rent = Rent::get()?
+                    .minimum_balance(anchor_spl::token::TokenAccount::LEN)
+                    .saturating_sub(unwrap_account.lamports());
+                if rent > 0 {
+                    transfer(
+                        CpiContext::new(
+                            ctx.accounts.system_program.to_account_info(),
+                            Transfer {
+                                from: ctx.accounts.user.to_account_info(),
+                                to: unwrap_account.to_account_info(),
+                            },
+                        ),
+                        rent,
+                    )?;
+                }
+                allocate(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.system_program.to_account_info(),
+                        Allocate {
+                            account_to_allocate: unwrap_account.to_account_info(),
+                        },
+                        unwrap_seeds,
+                    ),
+                    anchor_spl::token::TokenAccount::LEN as u64,
+                )?;
+                assign(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.system_program.to_account_info(),
+                        Assign {
+                            account_to_assign: unwrap_account.to_account_info(),
+                        },
+                        unwrap_seeds,
+                    ),
+                    &ctx.accounts.token_program.key(),
+                )?;
+
+                initialize_account3(CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    InitializeAccount3 {
+                        account: unwrap_account.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                        authority: market.to_account_info(),
+                    },
+                ))?;
+
+                transfer_checked(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        TransferChecked {
+                            from: bet_pool,
+                            mint: ctx.accounts.mint.to_account_info(),
+                            to: unwrap_account.to_account_info(),
+                            authority: market.to_account_info(),
+                        },
+                        signer_seeds,
+                    ),
+                    amount,
+                    ctx.accounts.mint.decimals,
+                )?;
+
+                close_account(CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    CloseAccount {
+                        account: unwrap_account.to_account_info(),
+                        destination: ctx.accounts.user.to_account_info(),
+                        authority: market.to_account_info(),
+                    },
+                    signer_seeds,
+                ))?;
+            }
+        }
     }
 
-    
+    if market.voided {
+        emit!(BetRefunded {
+            market: bet.market,
+            user: bet.user,
+            amount,
+        });
+    } else {
+        emit!(BetClaimed {
+            market: bet.market,
+            user: bet.user,
+            is_won: bet.is_won,
+            payout: amount,
+        });
+    }
 
     //just for increased redundancy because the bet account should be closed after
     bet.amount = 0;
@@ -95,7 +220,7 @@ pub struct ClaimBet<'info> {
         mut,
         seeds = [
             market.creator.key().as_ref(), 
-            &hash_to_bytes(&market.feed_id),
+            &market.feed_seed,
             &market.target_price.to_le_bytes(), 
             &market.market_duration.to_le_bytes(),
         ],
@@ -105,6 +230,13 @@ pub struct ClaimBet<'info> {
     pub market: Account<'info, Market>,
 
     #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
         token::mint = market.mint, 
         token::authority = market,
         seeds = [
@@ -113,9 +245,10 @@ pub struct ClaimBet<'info> {
         ],
         bump = market.higher_pool_bump,
     )]
-    pub higher_pool: Account<'info, TokenAccount>,
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
+        mut,
         token::mint = market.mint, 
         token::authority = market,
         seeds = [
@@ -124,14 +257,29 @@ pub struct ClaimBet<'info> {
         ],
         bump = market.lower_pool_bump,
     )]
-    pub lower_pool: Account<'info, TokenAccount>,
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
 
+    //created for the user when it was closed after betting
     #[account(
-        mut,
-        associated_token::mint = market.mint,
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_ata: Account<'info, TokenAccount>,
+    pub user_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    //wrapped SOL markets can pay out lamports instead, through this account
+    /// CHECK: created, filled and closed by the handler when the user has no token account
+    #[account(
+        mut,
+        seeds = [
+            UNWRAP_SEED.as_bytes(),
+            bet.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub unwrap_account: Option<UncheckedAccount<'info>>,
 
     #[account(
         mut,
@@ -152,8 +300,13 @@ pub struct ClaimBet<'info> {
     )]
     pub bet: Account<'info,Bet>,
 
-    pub price_update: Account<'info, PriceUpdateV2>,
+    /// CHECK: owner, layout and feed are validated by the oracle adapter of the market
+    pub price_update: UncheckedAccount<'info>,
+
+    #[account(address = market.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
 }
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::events::ReferralRewardsClaimed;
+use crate::states::*;
+use crate::MarketError;
+
+pub fn _claim_referral_rewards(
+    ctx: Context<ClaimReferralRewards>,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let referrer_stats = &mut ctx.accounts.referrer_stats;
+    let amount = referrer_stats.unclaimed_rewards;
+
+    require_gt!(amount, 0, MarketError::NoReferralRewards);
+
+    referrer_stats.unclaimed_rewards = 0;
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.referral_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.referrer_token_account.to_account_info(),
+                authority: config.to_account_info(),
+            },
+            &[&[CONFIG_SEED.as_bytes(), &[config.bump]]],
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    emit!(ReferralRewardsClaimed {
+        referrer: referrer_stats.referrer,
+        mint: referrer_stats.mint,
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    #[account(
+        mut,
+        seeds = [
+            REFERRER_SEED.as_bytes(),
+            referrer.key().as_ref(),
+            referrer_stats.mint.as_ref(),
+        ],
+        bump = referrer_stats.bump,
+        has_one = referrer @ MarketError::UnauthorizedUser,
+    )]
+    pub referrer_stats: Account<'info, ReferrerStats>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        token::mint = referrer_stats.mint,
+        token::authority = config,
+        seeds = [
+            REFERRAL_VAULT_SEED.as_bytes(),
+            referrer_stats.mint.as_ref(),
+        ],
+        bump,
+    )]
+    pub referral_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = referrer_stats.mint,
+    )]
+    pub referrer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub referrer: Signer<'info>,
+    #[account(address = referrer_stats.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::events::ResolutionDisputed;
+use crate::states::*;
+use crate::MarketError;
+
+//challenges a proposal by matching its bond, the arbiter then decides the final price. Not
+//stopped by pauses, a challenge window must never run out with nobody allowed to dispute
+pub fn _dispute_resolution(
+    ctx: Context<DisputeResolution>,
+) -> Result<()> {
+    let market = &ctx.accounts.market;
+    let resolution = &mut ctx.accounts.resolution;
+    let clock = Clock::get()?;
+
+    require!(resolution.status == ResolutionStatus::Proposed,MarketError::InvalidResolutionStatus);
+
+    let challenge_window = market
+        .optimistic_resolution
+        .as_ref()
+        .ok_or(MarketError::OptimisticResolutionDisabled)?
+        .challenge_window;
+    require_gte!(resolution.proposed_at.saturating_add(challenge_window),clock.slot,MarketError::ChallengeWindowOver);
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.disputer_ata.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.resolution_vault.to_account_info(),
+                authority: ctx.accounts.disputer.to_account_info(),
+            },
+        ),
+        resolution.bond,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    resolution.disputer = Some(ctx.accounts.disputer.key());
+    resolution.status = ResolutionStatus::Disputed;
+
+    emit!(ResolutionDisputed {
+        market: market.key(),
+        disputer: ctx.accounts.disputer.key(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DisputeResolution<'info> {
+    #[account(
+        seeds = [
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [
+            RESOLUTION_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = resolution.bump,
+    )]
+    pub resolution: Box<Account<'info, Resolution>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RESOLUTION_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = resolution.vault_bump,
+    )]
+    pub resolution_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = disputer,
+        associated_token::token_program = token_program,
+    )]
+    pub disputer_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub disputer: Signer<'info>,
+
+    #[account(address = market.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
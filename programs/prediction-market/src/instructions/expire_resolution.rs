@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::events::ResolutionExpired;
+use crate::instructions::release_resolution_vault;
+use crate::states::*;
+use crate::MarketError;
+
+//anyone can drop a dispute the arbiter left undecided past the arbitration window, both bonds
+//are returned and the market can be settled or voided again. The proposer receives the rent
+pub fn _expire_resolution(
+    ctx: Context<ExpireResolution>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let resolution = &ctx.accounts.resolution;
+    let clock = Clock::get()?;
+
+    require!(resolution.status == ResolutionStatus::Disputed,MarketError::InvalidResolutionStatus);
+    require!(!ctx.accounts.config.pause.claiming && !market.pause.claiming,MarketError::ActionPaused);
+
+    let challenge_window = market
+        .optimistic_resolution
+        .as_ref()
+        .ok_or(MarketError::OptimisticResolutionDisabled)?
+        .challenge_window;
+    //the arbiter can not decide while claiming is paused, pauses since the proposal extend the window
+    let paused_slots = market
+        .paused_slots(&ctx.accounts.config, clock.slot)
+        .saturating_sub(resolution.paused_slots_at_proposal);
+    let arbitration_deadline = resolution
+        .proposed_at
+        .saturating_add(challenge_window)
+        .saturating_add(ctx.accounts.config.arbitration_window)
+        .saturating_add(paused_slots);
+    require_gt!(clock.slot,arbitration_deadline,MarketError::ArbitrationWindowNotOver);
+
+    let disputer = resolution.disputer.ok_or(MarketError::InvalidResolutionStatus)?;
+    require_keys_eq!(ctx.accounts.disputer_ata.owner,disputer,MarketError::UnauthorizedUser);
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        market.creator.as_ref(),
+        &market.feed_seed,
+        &market.target_price.to_le_bytes(),
+        &market.market_duration.to_le_bytes(),
+        &[market.bump],
+    ]];
+
+    //both bonds were the same, the vault holds them less any transfer fee
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.resolution_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.disputer_ata.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        ctx.accounts.resolution_vault.amount / 2,
+        ctx.accounts.mint.decimals,
+    )?;
+    ctx.accounts.resolution_vault.reload()?;
+
+    release_resolution_vault(
+        market,
+        &ctx.accounts.token_program,
+        &ctx.accounts.resolution_vault,
+        &ctx.accounts.mint,
+        &ctx.accounts.proposer_ata,
+        &ctx.accounts.proposer,
+    )?;
+
+    market.resolution_pending = false;
+
+    emit!(ResolutionExpired {
+        market: market.key(),
+        proposer: resolution.proposer,
+        disputer,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExpireResolution<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        has_one = proposer,
+        close = proposer,
+        seeds = [
+            RESOLUTION_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = resolution.bump,
+    )]
+    pub resolution: Box<Account<'info, Resolution>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RESOLUTION_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = resolution.vault_bump,
+    )]
+    pub resolution_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = proposer,
+        associated_token::token_program = token_program,
+    )]
+    pub proposer_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+    )]
+    pub disputer_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+
+    #[account(address = market.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -1,62 +1,74 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{close_account, CloseAccount, Token, TokenAccount,transfer,Transfer};
+use anchor_spl::token_interface::*;
 
 use crate::constants::*;
+use crate::utils::release_bond_vault;
+use crate::math;
 use crate::states::*;
 use crate::MarketError;
-use crate::utils::hash_to_bytes;
 
 
+//closes a market once every bet is claimed and every LP has withdrawn, the creator receives the
+//seed and any surplus no LP backed
 pub fn _finalize_market(
     ctx: Context<FinalizeMarket>,
 ) -> Result<()> {
     let market = &ctx.accounts.market;
-    let _clock = Clock::get()?;
+    let clock = Clock::get()?;
     let higher_pool = &mut ctx.accounts.higher_pool;
     let lower_pool = &mut ctx.accounts.lower_pool;
 
     require!(market.initialization == MarketInitialization::InitializedPools,MarketError::InvalidMarketInitialization);
     require_keys_eq!(ctx.accounts.market_creator.key(),market.creator,MarketError::UnauthorizedUser);
-    // require_gt!(clock.slot,market.start_time + market.market_duration + MARKET_LOCK_PERIOD,MarketError::MarketLockPeriodNotOver);
-    require_eq!(higher_pool.amount + lower_pool.amount,INITIAL_USDC_POOL_AMOUNT*2,MarketError::NonZeroPools);
+    if ctx.accounts.config.market_lock_period > 0 {
+        require_gt!(clock.slot,math::checked_add(market.expiry()?, ctx.accounts.config.market_lock_period)?,MarketError::MarketLockPeriodNotOver);
+    }
+    require!(market.higher_stake == 0 && market.lower_stake == 0,MarketError::NonZeroPools);
+    require_eq!(ctx.accounts.lp_mint.supply,0,MarketError::NonZeroPools);
+    //the bond of a market nobody could settle goes to slash_creator_bond, not back to the creator
+    require!(!(market.voided_by_timeout && market.bond_vault_open),MarketError::BondPendingSlash);
 
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: higher_pool.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.creator_ata.to_account_info(),
                 authority: ctx.accounts.market.to_account_info(),
             },
             &[&[
             market.creator.key().as_ref(), 
-            &hash_to_bytes(&market.feed_id),
+            &market.feed_seed,
             &market.target_price.to_le_bytes(), 
             &market.market_duration.to_le_bytes(),
             &[ctx.accounts.market.bump],
         ]],
         ),
-        INITIAL_USDC_POOL_AMOUNT,
+        higher_pool.amount,
+        ctx.accounts.mint.decimals,
     )?;
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: lower_pool.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.creator_ata.to_account_info(),
                 authority: ctx.accounts.market.to_account_info(),
             },
             &[&[
             market.creator.key().as_ref(), 
-            &hash_to_bytes(&market.feed_id),
+            &market.feed_seed,
             &market.target_price.to_le_bytes(), 
             &market.market_duration.to_le_bytes(),
             &[ctx.accounts.market.bump],
         ]],
         ),
-        INITIAL_USDC_POOL_AMOUNT,
+        lower_pool.amount,
+        ctx.accounts.mint.decimals,
     )?;
 
     close_account(CpiContext::new_with_signer(
@@ -68,7 +80,7 @@ pub fn _finalize_market(
         }, 
         &[&[
             market.creator.key().as_ref(), 
-            &hash_to_bytes(&market.feed_id),
+            &market.feed_seed,
             &market.target_price.to_le_bytes(), 
             &market.market_duration.to_le_bytes(),
             &[ctx.accounts.market.bump],
@@ -84,13 +96,77 @@ pub fn _finalize_market(
         }, 
         &[&[
             market.creator.key().as_ref(), 
-            &hash_to_bytes(&market.feed_id),
+            &market.feed_seed,
             &market.target_price.to_le_bytes(), 
             &market.market_duration.to_le_bytes(),
             &[ctx.accounts.market.bump],
         ]],
     ))?;
 
+    //uncollected creator fees are swept to the creator before the vault is closed
+    let creator_fee_amount = ctx.accounts.creator_fee_vault.amount;
+    if creator_fee_amount > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.creator_fee_vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.creator_ata.to_account_info(),
+                    authority: ctx.accounts.market.to_account_info(),
+                },
+                &[&[
+                market.creator.key().as_ref(), 
+                &market.feed_seed,
+                &market.target_price.to_le_bytes(), 
+                &market.market_duration.to_le_bytes(),
+                &[ctx.accounts.market.bump],
+            ]],
+            ),
+            creator_fee_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(), 
+        CloseAccount{ 
+            account: ctx.accounts.creator_fee_vault.to_account_info(), 
+            destination: ctx.accounts.market_creator.to_account_info(), 
+            authority: ctx.accounts.market.to_account_info()
+        }, 
+        &[&[
+            market.creator.key().as_ref(), 
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(), 
+            &market.market_duration.to_le_bytes(),
+            &[ctx.accounts.market.bump],
+        ]],
+    ))?;
+
+    //a market closed by its creator gets the bond back
+    if market.bond_vault_open {
+        let bond_vault = ctx.accounts.bond_vault.as_ref().ok_or(MarketError::MissingBondVault)?;
+        release_bond_vault(
+            market,
+            &ctx.accounts.token_program,
+            bond_vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.creator_ata,
+            &ctx.accounts.market_creator.to_account_info(),
+        )?;
+    }
+
+    //frees the slot of the market in the quota of its creator
+    if market.counted_by_registry {
+        let creator_registry = ctx
+            .accounts
+            .creator_registry
+            .as_mut()
+            .ok_or(MarketError::CreatorNotRegistered)?;
+        creator_registry.open_markets = creator_registry.open_markets.saturating_sub(1);
+    }
+
     Ok(())
 }
 
@@ -101,7 +177,7 @@ pub struct FinalizeMarket<'info> {
         close = market_creator,
         seeds = [
             market.creator.key().as_ref(), 
-            &hash_to_bytes(&market.feed_id),
+            &market.feed_seed,
             &market.target_price.to_le_bytes(), 
             &market.market_duration.to_le_bytes(),
         ],
@@ -109,9 +185,14 @@ pub struct FinalizeMarket<'info> {
     )]
     pub market: Account<'info, Market>,
 
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
     #[account(
         mut,
-        close = market_creator,
         token::mint = market.mint, 
         token::authority = market,
         seeds = [
@@ -120,11 +201,10 @@ pub struct FinalizeMarket<'info> {
         ],
         bump = market.higher_pool_bump,
     )]
-    pub higher_pool: Account<'info, TokenAccount>,
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        close = market_creator,
         token::mint = market.mint, 
         token::authority = market,
         seeds = [
@@ -133,14 +213,36 @@ pub struct FinalizeMarket<'info> {
         ],
         bump = market.lower_pool_bump,
     )]
-    pub lower_pool: Account<'info, TokenAccount>,
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [
+            LP_MINT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.lp_mint_bump,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
         associated_token::mint = market.mint,
         associated_token::authority = market_creator,
+        associated_token::token_program = token_program,
     )]
-    pub creator_ata: Account<'info, TokenAccount>,
+    pub creator_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = market.mint, 
+        token::authority = market,
+        seeds = [
+            CREATOR_FEE_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.creator_fee_vault_bump,
+    )]
+    pub creator_fee_vault: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
@@ -148,7 +250,32 @@ pub struct FinalizeMarket<'info> {
     )]
     pub market_creator: Signer<'info>,
     
+    #[account(address = market.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            CREATOR_REGISTRY_SEED.as_bytes(),
+            market.creator.as_ref(),
+        ],
+        bump = creator_registry.bump,
+    )]
+    pub creator_registry: Option<Account<'info, CreatorRegistry>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        seeds = [
+            BOND_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.bond_vault_bump,
+    )]
+    pub bond_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 
 }
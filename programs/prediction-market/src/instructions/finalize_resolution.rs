@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::events::ResolutionFinalized;
+use crate::states::*;
+use crate::MarketError;
+
+//settles the market on an unchallenged proposal once the challenge window is over
+//and returns the bond to the proposer, who also receives the rent of the resolution
+pub fn _finalize_resolution(
+    ctx: Context<FinalizeResolution>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let resolution = &mut ctx.accounts.resolution;
+    let clock = Clock::get()?;
+
+    require!(resolution.status == ResolutionStatus::Proposed,MarketError::InvalidResolutionStatus);
+    require!(!ctx.accounts.config.pause.claiming && !market.pause.claiming,MarketError::ActionPaused);
+
+    let challenge_window = market
+        .optimistic_resolution
+        .as_ref()
+        .ok_or(MarketError::OptimisticResolutionDisabled)?
+        .challenge_window;
+    require_gt!(clock.slot,resolution.proposed_at.saturating_add(challenge_window),MarketError::ChallengeWindowNotOver);
+
+    let price = resolution.proposed_price;
+    apply_resolution(market, resolution, price);
+
+    let payout = ctx.accounts.resolution_vault.amount;
+    release_resolution_vault(
+        market,
+        &ctx.accounts.token_program,
+        &ctx.accounts.resolution_vault,
+        &ctx.accounts.mint,
+        &ctx.accounts.proposer_ata,
+        &ctx.accounts.proposer,
+    )?;
+
+    emit!(ResolutionFinalized {
+        market: market.key(),
+        price,
+        disputed: false,
+        bond_recipient: resolution.proposer,
+        payout,
+    });
+
+    Ok(())
+}
+
+//stores the resolved price as the market final price so claim_bet settles on it
+pub fn apply_resolution(market: &mut Market, resolution: &mut Resolution, price: u64) {
+    market.final_price = Some(price);
+    market.final_price_truncated = false;
+    market.final_conf = None;
+    market.final_conf_exponent = 0;
+    market.resolution_pending = false;
+
+    resolution.resolved_price = Some(price);
+    resolution.status = ResolutionStatus::Resolved;
+}
+
+//pays the bonds held for a resolution to `recipient` and closes the vault, the vault rent
+//goes back to the proposer who funded it
+pub fn release_resolution_vault<'info>(
+    market: &Account<'info, Market>,
+    token_program: &Interface<'info, TokenInterface>,
+    resolution_vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    recipient: &InterfaceAccount<'info, TokenAccount>,
+    proposer: &SystemAccount<'info>,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        market.creator.as_ref(),
+        &market.feed_seed,
+        &market.target_price.to_le_bytes(),
+        &market.market_duration.to_le_bytes(),
+        &[market.bump],
+    ]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: resolution_vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: recipient.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        resolution_vault.amount,
+        mint.decimals,
+    )?;
+
+    close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: resolution_vault.to_account_info(),
+            destination: proposer.to_account_info(),
+            authority: market.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
+
+#[derive(Accounts)]
+pub struct FinalizeResolution<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        has_one = proposer,
+        close = proposer,
+        seeds = [
+            RESOLUTION_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = resolution.bump,
+    )]
+    pub resolution: Box<Account<'info, Resolution>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RESOLUTION_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = resolution.vault_bump,
+    )]
+    pub resolution_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = proposer,
+        associated_token::token_program = token_program,
+    )]
+    pub proposer_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+
+    #[account(address = market.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::program::PredictionMarket;
+use crate::states::*;
+use crate::MarketError;
+
+//creates the protocol config, only the program upgrade authority can become the first admin
+pub fn _initialize_config(
+    ctx: Context<InitializeConfig>,
+    params: ConfigParams,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    config.admin = ctx.accounts.admin.key();
+    config.bump = ctx.bumps.config;
+    config.apply(&params)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ MarketError::UnauthorizedUser,
+    )]
+    pub program: Program<'info, PredictionMarket>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ MarketError::UnauthorizedUser,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::constants::*;
+use crate::utils::lock_creator_bond;
+use crate::MarketError;
+use crate::states::{CollateralConfig,Config,CreatorRegistry,FeedVersion,Market,MarketInitialization,MarketKind,MarketParams};
+
+//creates a yes/no market on an off-chain event, `resolver` sets the outcome after expiry and
+//before the end of the settlement window, otherwise the market is voided
+pub fn _initialize_event_market(
+    ctx: Context<InitializeEventMarket>,
+    event_id: [u8; 32], // chosen by the creator, for example the hash of the question
+    market_duration: u64,
+    resolver: Pubkey,
+    params: MarketParams,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    require!(!config.pause.creation, MarketError::ActionPaused);
+    require_gte!(market_duration, config.min_market_duration, MarketError::ShortMarketDuration);
+    require!(params.optimistic_resolution.is_none(), MarketError::InvalidMarketKind);
+    require!(params.extra_feed_ids.is_empty(), MarketError::InvalidMarketKind);
+
+    let market = &mut ctx.accounts.market;
+
+    let clock = Clock::get()?;
+    market.start_time = clock.slot;
+    market.start_timestamp = clock.unix_timestamp;
+    market.protocol_paused_at_start = config.claiming_paused.at(clock.slot);
+
+    market.apply_params(params, config)?;
+
+    match ctx.accounts.creator_registry.as_mut() {
+        Some(creator_registry) => {
+            creator_registry.open_market(&[])?;
+            market.counted_by_registry = true;
+        }
+        None => require!(!config.permissioned_creation, MarketError::CreatorNotRegistered),
+    }
+
+    market.creator_bond = lock_creator_bond(
+        config,
+        &ctx.accounts.collateral_config,
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        &ctx.accounts.creator_ata,
+        &ctx.accounts.market_creator,
+        &mut ctx.accounts.bond_vault,
+    )?;
+    market.mint = ctx.accounts.mint.key();
+    market.bond_vault_bump = ctx.bumps.bond_vault;
+    market.bond_vault_open = true;
+
+    market.kind = MarketKind::Event;
+    market.resolver = Some(resolver);
+    market.market_duration = market_duration;
+    //durations running past the last slot are rejected, the expiry must be representable
+    market.expiry()?;
+
+    market.feed_seed = event_id;
+    market.feed_version = FeedVersion::Event;
+
+    market.creator = ctx.accounts.market_creator.key();
+
+    market.bump = ctx.bumps.market;
+
+    market.initialization = MarketInitialization::InitializedMarket;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(event_id:[u8; 32],market_duration:u64)]
+pub struct InitializeEventMarket<'info> {
+    #[account(
+        init,
+        payer = market_creator,
+        space = 8 + Market::INIT_SPACE,
+        seeds = [
+            market_creator.key().as_ref(),
+            event_id.as_ref(),
+            &0u64.to_le_bytes(), // target_price is unused by event markets
+            &market_duration.to_le_bytes(),
+        ],
+        bump
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [
+            CREATOR_REGISTRY_SEED.as_bytes(),
+            market_creator.key().as_ref(),
+        ],
+        bump = creator_registry.bump,
+    )]
+    pub creator_registry: Option<Account<'info, CreatorRegistry>>,
+
+    //collateral of the market, the creator bond is posted in it
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    //only mints added by the admin can back a market
+    #[account(
+        seeds = [
+            COLLATERAL_CONFIG_SEED.as_bytes(),
+            mint.key().as_ref(),
+        ],
+        bump = collateral_config.bump,
+    )]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+
+    //returned on finalize_market or cancel_market, slashed if the market is abandoned unresolved
+    #[account(
+        init,
+        payer = market_creator,
+        token::mint = mint,
+        token::authority = market,
+        seeds = [
+            BOND_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump
+    )]
+    pub bond_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = market_creator,
+        associated_token::token_program = token_program,
+    )]
+    pub creator_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub market_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::states::ManualOracle;
+
+//creates a feed the authority publishes prices to, markets use its address as feed id
+pub fn _initialize_manual_oracle(
+    ctx: Context<InitializeManualOracle>,
+    feed_id: [u8; 32],
+    exponent: i32,
+) -> Result<()> {
+    let oracle = &mut ctx.accounts.manual_oracle;
+
+    oracle.authority = ctx.accounts.authority.key();
+    oracle.feed_id = feed_id;
+    oracle.exponent = exponent;
+    oracle.bump = ctx.bumps.manual_oracle;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(feed_id: [u8; 32])]
+pub struct InitializeManualOracle<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ManualOracle::INIT_SPACE,
+        seeds = [
+            MANUAL_ORACLE_SEED.as_bytes(),
+            authority.key().as_ref(),
+            feed_id.as_ref(),
+        ],
+        bump
+    )]
+    pub manual_oracle: Account<'info, ManualOracle>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,29 +1,99 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
+use crate::constants::*;
 use crate::MarketError;
-use crate::states::{Market,MarketInitialization};
-use crate::utils::hash_to_bytes;
+use crate::states::{CollateralConfig,Config,CreatorRegistry,FeedRegistry,FeedVersion,Market,MarketInitialization,MarketKind,MarketParams,OracleFeed};
+use crate::utils::{lock_creator_bond, parse_feed_id};
 
-pub fn _initialize_market(
-    ctx: Context<InitializeMarket>,
+//registries of the extra feeds in `params` are passed as remaining accounts in the same order
+pub fn _initialize_market<'info>(
+    ctx: Context<'_, '_, 'info, 'info, InitializeMarket<'info>>,
     target_price: u64,
-    feed_id: String, // from https://pyth.network/developers/price-feed-ids#solana-stables
+    feed_id: Vec<u8>, // raw 32 bytes or hex with or without 0x, see utils::parse_feed_id
     market_duration: u64,
+    params: MarketParams,
 ) -> Result<()> {
-    require_eq!(feed_id.len(), 66, MarketError::IncorrectFeedIDLength);
-    require_gte!(market_duration, 1200, MarketError::ShortMarketDuration); //more than one hour
+    let feed_id = parse_feed_id(&feed_id)?;
+
+    let feed_registry = &ctx.accounts.feed_registry;
+    require!(feed_registry.enabled, MarketError::FeedDisabled);
+
+    let config = &ctx.accounts.config;
+    require!(!config.pause.creation, MarketError::ActionPaused);
+    require_gte!(market_duration, config.min_market_duration, MarketError::ShortMarketDuration);
 
     let market = &mut ctx.accounts.market;
     
     let clock = Clock::get()?;
     market.start_time = clock.slot;
+    market.start_timestamp = clock.unix_timestamp;
+    market.protocol_paused_at_start = config.claiming_paused.at(clock.slot);
    
+    let extra_feed_ids = params.extra_feed_ids.clone();
+    market.apply_params(params, config)?;
+
+    match ctx.accounts.creator_registry.as_mut() {
+        Some(creator_registry) => {
+            let market_feed_ids = [&[feed_id][..], &extra_feed_ids].concat();
+            creator_registry.open_market(&market_feed_ids)?;
+            market.counted_by_registry = true;
+        }
+        None => require!(!config.permissioned_creation, MarketError::CreatorNotRegistered),
+    }
+
+    market.creator_bond = lock_creator_bond(
+        config,
+        &ctx.accounts.collateral_config,
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        &ctx.accounts.creator_ata,
+        &ctx.accounts.market_creator,
+        &mut ctx.accounts.bond_vault,
+    )?;
+    market.mint = ctx.accounts.mint.key();
+    market.bond_vault_bump = ctx.bumps.bond_vault;
+    market.bond_vault_open = true;
+
+    require_gte!(ctx.remaining_accounts.len(), extra_feed_ids.len(), MarketError::IncompleteOracleAccounts);
+    let mut extra_feeds = Vec::with_capacity(extra_feed_ids.len());
+    for (extra_feed_id, registry_info) in extra_feed_ids.iter().zip(ctx.remaining_accounts) {
+        //every feed is read once so a single publisher can not make up the agreement
+        require!(
+            *extra_feed_id != feed_id && !extra_feeds.iter().any(|feed: &OracleFeed| feed.feed_id == *extra_feed_id),
+            MarketError::InvalidOracleAgreement
+        );
+
+        let (registry_address, _) = Pubkey::find_program_address(
+            &[FEED_REGISTRY_SEED.as_bytes(), extra_feed_id],
+            ctx.program_id,
+        );
+        require_keys_eq!(registry_info.key(), registry_address, MarketError::InvalidOracleAgreement);
+
+        let registry = Account::<FeedRegistry>::try_from(registry_info)?;
+        require!(registry.enabled, MarketError::FeedDisabled);
+
+        extra_feeds.push(OracleFeed {
+            source: registry.oracle_source.clone(),
+            feed_id: *extra_feed_id,
+            max_price_age_secs: registry.max_price_age_secs,
+            max_conf_bps: registry.max_conf_bps,
+        });
+    }
+    market.extra_feeds = extra_feeds;
+
+    market.kind = MarketKind::Price;
     market.target_price = target_price;
     market.market_duration = market_duration;
+    //durations running past the last slot are rejected, the expiry must be representable
+    market.expiry()?;
+    market.oracle_source = feed_registry.oracle_source.clone();
+    market.max_conf_bps = feed_registry.max_conf_bps;
+    market.max_price_age_secs = feed_registry.max_price_age_secs;
 
-    let mut feed_data = [0u8; 66];
-    feed_data[..feed_id.as_bytes().len()].copy_from_slice(feed_id.as_bytes());
-    market.feed_id = feed_data;    
+    market.feed_id = feed_id;
+    market.feed_seed = feed_id;
+    market.feed_version = FeedVersion::Bytes;
 
     market.creator = ctx.accounts.market_creator.key();
     
@@ -35,7 +105,7 @@ pub fn _initialize_market(
 }
 
 #[derive(Accounts)]
-#[instruction(target_price:u64,feed_id:String,market_duration:u64)]
+#[instruction(target_price:u64,feed_id:Vec<u8>,market_duration:u64)]
 pub struct InitializeMarket<'info> {
     #[account(
         init,
@@ -43,7 +113,7 @@ pub struct InitializeMarket<'info> {
         space = 8 + Market::INIT_SPACE,
         seeds = [
             market_creator.key().as_ref(), 
-            &hash_to_bytes(feed_id.as_bytes()),
+            &parse_feed_id(&feed_id).unwrap_or_default(), // invalid ids are rejected in the handler
             &target_price.to_le_bytes(), 
             &market_duration.to_le_bytes(),
         ],
@@ -51,7 +121,68 @@ pub struct InitializeMarket<'info> {
     )]
     pub market: Account<'info, Market>,
 
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [
+            FEED_REGISTRY_SEED.as_bytes(),
+            &parse_feed_id(&feed_id).unwrap_or_default(),
+        ],
+        bump = feed_registry.bump,
+    )]
+    pub feed_registry: Account<'info, FeedRegistry>,
+
+    #[account(
+        mut,
+        seeds = [
+            CREATOR_REGISTRY_SEED.as_bytes(),
+            market_creator.key().as_ref(),
+        ],
+        bump = creator_registry.bump,
+    )]
+    pub creator_registry: Option<Account<'info, CreatorRegistry>>,
+
+    //collateral of the market, the creator bond is posted in it
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    //only mints added by the admin can back a market
+    #[account(
+        seeds = [
+            COLLATERAL_CONFIG_SEED.as_bytes(),
+            mint.key().as_ref(),
+        ],
+        bump = collateral_config.bump,
+    )]
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
+
+    //returned on finalize_market or cancel_market, slashed if the market is abandoned unresolved
+    #[account(
+        init,
+        payer = market_creator,
+        token::mint = mint,
+        token::authority = market,
+        seeds = [
+            BOND_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump
+    )]
+    pub bond_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = market_creator,
+        associated_token::token_program = token_program,
+    )]
+    pub creator_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
     #[account(mut)]
     pub market_creator: Signer<'info>,
     pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
@@ -1,52 +1,71 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::*;
+use anchor_spl::token_interface::*;
 
 use crate::constants::*;
 use crate::states::*;
+use crate::utils::received_amount;
 use crate::MarketError;
-use crate::utils::hash_to_bytes;
 
 pub fn _initialize_pools(
     ctx: Context<InitializePools>,
 ) -> Result<()> {
     let market = &mut ctx.accounts.market;
     require!(market.initialization == MarketInitialization::InitializedMarket,MarketError::InvalidMarketInitialization);
-    // require_eq!(
-    //     ctx.accounts.pool_token_mint.key().to_string(),
-    //     USDC_MINT, //more tokens can be allowed in the future
-    //     MarketError::InvalidPoolMint
-    // ); 
+    require!(!ctx.accounts.config.pause.creation && !market.pause.creation, MarketError::ActionPaused);
+
+    //the collateral was chosen and checked when the creator bond was posted
+    let collateral_config = &ctx.accounts.collateral_config;
     
-    transfer(
+    transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.user_ata.to_account_info(),
+                mint: ctx.accounts.pool_token_mint.to_account_info(),
                 to: ctx.accounts.higher_pool.to_account_info(),
                 authority: ctx.accounts.market_creator.to_account_info(),
             },
         ),
-        INITIAL_USDC_POOL_AMOUNT,
+        ctx.accounts.config.pool_seed_amount,
+        ctx.accounts.pool_token_mint.decimals,
     )?;
 
-    transfer(
+    transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.user_ata.to_account_info(),
+                mint: ctx.accounts.pool_token_mint.to_account_info(),
                 to: ctx.accounts.lower_pool.to_account_info(),
                 authority: ctx.accounts.market_creator.to_account_info(),
             },
         ),
-        INITIAL_USDC_POOL_AMOUNT,
+        ctx.accounts.config.pool_seed_amount,
+        ctx.accounts.pool_token_mint.decimals,
     )?;
 
     //Nothing after this comments seems to run at all
-    
-    market.mint = ctx.accounts.pool_token_mint.key();
+
+    //with a transfer fee the pools hold less than the seed that was sent
+    let higher_seed = received_amount(&mut ctx.accounts.higher_pool, 0)?;
+    let lower_seed = received_amount(&mut ctx.accounts.lower_pool, 0)?;
+    market.seed_amount = higher_seed.min(lower_seed);
+
+    //limits the creator set in token units narrow the limits of the collateral mint
+    let (mut min_bet, mut max_bet) = (collateral_config.min_bet, collateral_config.max_bet);
+    if let Some(limits) = &market.bet_limits {
+        let (market_min_bet, market_max_bet) = limits.to_base_units(ctx.accounts.pool_token_mint.decimals)?;
+        min_bet = min_bet.max(market_min_bet);
+        max_bet = max_bet.min(market_max_bet);
+    }
+    require_gte!(max_bet, min_bet, MarketError::InvalidBetLimits);
+    market.min_bet = min_bet;
+    market.max_bet = max_bet;
     
     market.lower_pool_bump = ctx.bumps.lower_pool;
     market.higher_pool_bump = ctx.bumps.higher_pool;
+    market.lp_mint_bump = ctx.bumps.lp_mint;
+    market.creator_fee_vault_bump = ctx.bumps.creator_fee_vault;
 
     market.initialization = MarketInitialization::InitializedPools;
 
@@ -59,7 +78,7 @@ pub struct InitializePools<'info> {
         mut,
         seeds = [
             market.creator.key().as_ref(), 
-            &hash_to_bytes(&market.feed_id),
+            &market.feed_seed,
             &market.target_price.to_le_bytes(), 
             &market.market_duration.to_le_bytes(),
         ],
@@ -78,7 +97,7 @@ pub struct InitializePools<'info> {
         ],
         bump
     )]
-    pub higher_pool: Box<Account<'info, TokenAccount>>,
+    pub higher_pool: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init,
@@ -91,17 +110,76 @@ pub struct InitializePools<'info> {
         ],
         bump
     )]
-    pub lower_pool: Box<Account<'info, TokenAccount>>,
+    pub lower_pool: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //share token minted to liquidity providers of this market
+    #[account(
+        init,
+        payer = market_creator,
+        mint::decimals = pool_token_mint.decimals,
+        mint::authority = market,
+        seeds = [
+            LP_MINT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    //protocol fees for every market using this mint accumulate here
+    #[account(
+        init_if_needed,
+        payer = market_creator,
+        token::mint = pool_token_mint,
+        token::authority = config,
+        seeds = [
+            PROTOCOL_FEE_VAULT_SEED.as_bytes(),
+            pool_token_mint.key().as_ref(),
+        ],
+        bump
+    )]
+    pub protocol_fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = market_creator,
+        token::mint = pool_token_mint,
+        token::authority = market,
+        seeds = [
+            CREATOR_FEE_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump
+    )]
+    pub creator_fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = pool_token_mint,
         associated_token::authority = market.creator,
+        associated_token::token_program = token_program,
+    )]
+    pub user_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //only mints added by the admin can back a market
+    #[account(
+        seeds = [
+            COLLATERAL_CONFIG_SEED.as_bytes(),
+            pool_token_mint.key().as_ref(),
+        ],
+        bump = collateral_config.bump,
     )]
-    pub user_ata: Box<Account<'info, TokenAccount>>,
+    pub collateral_config: Box<Account<'info, CollateralConfig>>,
 
     //token mint account that bets are gonna be made with e.g USDC
-    pub pool_token_mint: Account<'info,Mint>,
+    #[account(address = market.mint @ MarketError::InvalidPoolMint)]
+    pub pool_token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
@@ -110,6 +188,6 @@ pub struct InitializePools<'info> {
     pub market_creator: Signer<'info>,
     
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 
 }
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+use num_traits::*;
+
+use crate::constants::*;
+use crate::math;
+use crate::states::*;
+use crate::MarketError;
+
+//rewrites a bet placed on a legacy market into the current layout so it can be claimed,
+//legacy bets paid no fees so their whole amount is the stake
+pub fn _migrate_legacy_bet(
+    ctx: Context<MigrateLegacyBet>,
+) -> Result<()> {
+    let bet_info = ctx.accounts.bet.to_account_info();
+    let market = &mut ctx.accounts.market;
+
+    require_keys_eq!(*bet_info.owner, crate::ID, MarketError::InvalidLegacyBet);
+    require_eq!(bet_info.data_len(), 8 + LegacyBet::INIT_SPACE, MarketError::InvalidLegacyBet);
+
+    let legacy = {
+        let data = bet_info.try_borrow_data()?;
+        require!(data[..8] == Bet::DISCRIMINATOR, MarketError::InvalidLegacyBet);
+        LegacyBet::deserialize(&mut &data[8..])?
+    };
+
+    require_keys_eq!(legacy.market, market.key(), MarketError::BetMarketMismatch);
+    require_eq!(legacy.claimed, false, MarketError::BetIsClaimed);
+
+    let bet_address = Pubkey::create_program_address(
+        &[
+            BET_SEED.as_bytes(),
+            legacy.user.as_ref(),
+            legacy.market.as_ref(),
+            legacy.amount.to_le_bytes().as_ref(),
+            &legacy.direction.to_u8().unwrap().to_le_bytes(),
+            &[legacy.bump],
+        ],
+        &crate::ID,
+    ).map_err(|_| MarketError::InvalidLegacyBet)?;
+    require_keys_eq!(bet_address, bet_info.key(), MarketError::InvalidLegacyBet);
+
+    //the bet is now reserved against the pools like any bet placed after the upgrade
+    let payout = math::payout(legacy.amount, legacy.odds)?;
+    match legacy.direction {
+        Direction::Higher => {
+            market.higher_liability = market.higher_liability.checked_add(payout).ok_or(MarketError::MathOverflow)?;
+            market.higher_stake = market.higher_stake.checked_add(legacy.amount).ok_or(MarketError::MathOverflow)?;
+        }
+        Direction::Lower => {
+            market.lower_liability = market.lower_liability.checked_add(payout).ok_or(MarketError::MathOverflow)?;
+            market.lower_stake = market.lower_stake.checked_add(legacy.amount).ok_or(MarketError::MathOverflow)?;
+        }
+    }
+
+    let bet = Bet {
+        user: legacy.user,
+        market: legacy.market,
+        amount: legacy.amount,
+        direction: legacy.direction,
+        claimed: legacy.claimed,
+        bump: legacy.bump,
+        initialized: legacy.initialized,
+        odds: legacy.odds,
+        is_won: legacy.is_won,
+        stake: legacy.amount,
+        protocol_fee: 0,
+        creator_fee: 0,
+        lp_fee: 0,
+        dynamic_fee_bps: 0,
+        dynamic_fee: 0,
+        referrer: None,
+        referral_fee: 0,
+    };
+
+    let space = 8 + Bet::INIT_SPACE;
+    let rent = Rent::get()?.minimum_balance(space).saturating_sub(bet_info.lamports());
+    if rent > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: bet_info.clone(),
+                },
+            ),
+            rent,
+        )?;
+    }
+    bet_info.realloc(space, true)?;
+
+    let mut data = bet_info.try_borrow_mut_data()?;
+    bet.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateLegacyBet<'info> {
+    //only markets migrated from the legacy layout can hold legacy bets
+    #[account(
+        mut,
+        constraint = market.feed_version == FeedVersion::LegacyHex @ MarketError::InvalidLegacyBet,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// CHECK: legacy layout, owner, discriminator and address are validated in the handler
+    #[account(mut)]
+    pub bet: UncheckedAccount<'info>,
+
+    //anyone can pay the extra rent, the bet still pays out to its user only
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,199 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::constants::*;
+use crate::states::*;
+use crate::utils::{hash_to_bytes, parse_feed_id};
+use crate::MarketError;
+
+//rewrites a market created with the 66-byte hex feed id layout into the current layout,
+//the feed seed keeps the hash of the hex id so the market address and its pool PDAs do not change
+pub fn _migrate_legacy_market(
+    ctx: Context<MigrateLegacyMarket>,
+) -> Result<()> {
+    let market_info = ctx.accounts.market.to_account_info();
+
+    require_keys_eq!(*market_info.owner, crate::ID, MarketError::InvalidLegacyMarket);
+    require_eq!(market_info.data_len(), 8 + LegacyMarket::INIT_SPACE, MarketError::InvalidLegacyMarket);
+
+    let legacy = {
+        let data = market_info.try_borrow_data()?;
+        require!(data[..8] == Market::DISCRIMINATOR, MarketError::InvalidLegacyMarket);
+        LegacyMarket::deserialize(&mut &data[8..])?
+    };
+
+    require_keys_eq!(legacy.creator, ctx.accounts.market_creator.key(), MarketError::UnauthorizedUser);
+
+    require_keys_eq!(legacy.mint, ctx.accounts.mint.key(), MarketError::InvalidLegacyMarket);
+
+    //legacy markets with pools never had an lp mint or a creator fee vault, they are created here
+    //while markets without pools get both from initialize_pools like any other market
+    let (lp_mint_bump, creator_fee_vault_bump) = match legacy.initialization {
+        MarketInitialization::InitializedPools => {
+            require!(
+                ctx.accounts.lp_mint.is_some() && ctx.accounts.creator_fee_vault.is_some(),
+                MarketError::MissingLegacyMarketVaults
+            );
+            (
+                ctx.bumps.lp_mint.ok_or(MarketError::MissingLegacyMarketVaults)?,
+                ctx.bumps.creator_fee_vault.ok_or(MarketError::MissingLegacyMarketVaults)?,
+            )
+        }
+        MarketInitialization::InitializedMarket => {
+            require!(
+                ctx.accounts.lp_mint.is_none() && ctx.accounts.creator_fee_vault.is_none(),
+                MarketError::InvalidMarketInitialization
+            );
+            (0, 0)
+        }
+    };
+
+    let config = &ctx.accounts.config;
+    let feed_seed = hash_to_bytes(&legacy.feed_id);
+    let market_address = Pubkey::create_program_address(
+        &[
+            legacy.creator.as_ref(),
+            &feed_seed,
+            &legacy.target_price.to_le_bytes(),
+            &legacy.market_duration.to_le_bytes(),
+            &[legacy.bump],
+        ],
+        &crate::ID,
+    ).map_err(|_| MarketError::InvalidLegacyMarket)?;
+    require_keys_eq!(market_address, market_info.key(), MarketError::InvalidLegacyMarket);
+
+    //legacy markets did not record when they started in unix time
+    let clock = Clock::get()?;
+    let elapsed_ms = clock.slot.saturating_sub(legacy.start_time).saturating_mul(ESTIMATED_SLOT_MS);
+    let legacy_start_timestamp = clock
+        .unix_timestamp
+        .saturating_sub(i64::try_from(elapsed_ms / 1000).unwrap_or(i64::MAX));
+
+    let market = Market {
+        creator: legacy.creator,
+        target_price: legacy.target_price,
+        start_time: legacy.start_time,
+        market_duration: legacy.market_duration,
+        bump: legacy.bump,
+        mint: legacy.mint,
+        higher_pool_bump: legacy.higher_pool_bump,
+        final_price: legacy.final_price,
+        lower_pool_bump: legacy.lower_pool_bump,
+        feed_id: parse_feed_id(&legacy.feed_id)?,
+        initialization: legacy.initialization,
+        lp_mint_bump,
+        higher_liability: 0,
+        lower_liability: 0,
+        creator_fee_bps: 0,
+        creator_fee_vault_bump,
+        fee_curve: None,
+        oracle_source: OracleSource::PythPull,
+        max_conf_bps: BPS_DENOMINATOR as u16,
+        final_conf: None,
+        voided: false,
+        higher_stake: 0,
+        lower_stake: 0,
+        target_exponent: 0,
+        final_price_truncated: false,
+        max_price_age_secs: config.max_price_age_secs,
+        settlement_window: config.default_settlement_window,
+        min_pyth_signatures: None,
+        feed_seed,
+        feed_version: FeedVersion::LegacyHex,
+        optimistic_resolution: None,
+        resolution_pending: false,
+        kind: MarketKind::Price,
+        resolver: None,
+        event_outcome: None,
+        extra_feeds: Vec::new(),
+        min_agreeing_readings: 1,
+        agreement_tolerance_bps: 0,
+        settlement_readings: Vec::new(),
+        seed_amount: LEGACY_POOL_SEED_AMOUNT,
+        min_bet: 0,
+        max_bet: u64::MAX,
+        bet_limits: None,
+        pause: PauseFlags::default(),
+        counted_by_registry: false,
+        creator_bond: 0,
+        bond_vault_bump: 0,
+        bond_vault_open: false,
+        start_timestamp: legacy_start_timestamp,
+        voided_by_timeout: false,
+        final_conf_exponent: 0,
+        claiming_paused: PausedSlots::default(),
+        protocol_paused_at_start: config.claiming_paused.at(clock.slot),
+    };
+
+    let space = 8 + Market::INIT_SPACE;
+    let rent = Rent::get()?.minimum_balance(space).saturating_sub(market_info.lamports());
+    if rent > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.market_creator.to_account_info(),
+                    to: market_info.clone(),
+                },
+            ),
+            rent,
+        )?;
+    }
+    market_info.realloc(space, true)?;
+
+    let mut data = market_info.try_borrow_mut_data()?;
+    market.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateLegacyMarket<'info> {
+    /// CHECK: legacy layout, owner, discriminator and address are validated in the handler
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+
+    //required when the legacy market already has pools
+    #[account(
+        init,
+        payer = market_creator,
+        mint::decimals = mint.decimals,
+        mint::authority = market,
+        seeds = [
+            LP_MINT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump
+    )]
+    pub lp_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        init,
+        payer = market_creator,
+        token::mint = mint,
+        token::authority = market,
+        seeds = [
+            CREATOR_FEE_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump
+    )]
+    pub creator_fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    //legacy markets get the loosest price age and the default settlement window of the protocol
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    //collateral of the legacy market, checked against it in the handler
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub market_creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
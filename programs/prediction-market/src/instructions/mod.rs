@@ -1,6 +1,12 @@
 pub mod initialize_market;
 pub use initialize_market::*;
 
+pub mod initialize_event_market;
+pub use initialize_event_market::*;
+
+pub mod resolve_market;
+pub use resolve_market::*;
+
 pub mod initialize_pools;
 pub use initialize_pools::*;
 
@@ -15,3 +21,99 @@ pub use place_bet::*;
 
 pub mod claim_bet;
 pub use claim_bet::*;
+
+pub mod settle_market;
+pub use settle_market::*;
+
+pub mod void_market;
+pub use void_market::*;
+
+pub mod slash_creator_bond;
+pub use slash_creator_bond::*;
+
+pub mod add_liquidity;
+pub use add_liquidity::*;
+
+pub mod remove_liquidity;
+pub use remove_liquidity::*;
+
+pub mod initialize_config;
+pub use initialize_config::*;
+
+pub mod update_config;
+pub use update_config::*;
+
+pub mod propose_admin;
+pub use propose_admin::*;
+
+pub mod accept_admin;
+pub use accept_admin::*;
+
+pub mod set_protocol_pause;
+pub use set_protocol_pause::*;
+
+pub mod set_market_pause;
+pub use set_market_pause::*;
+
+pub mod register_feed;
+pub use register_feed::*;
+
+pub mod update_feed;
+pub use update_feed::*;
+
+pub mod add_collateral_mint;
+pub use add_collateral_mint::*;
+
+pub mod remove_collateral_mint;
+pub use remove_collateral_mint::*;
+
+pub mod register_creator;
+pub use register_creator::*;
+
+pub mod update_creator;
+pub use update_creator::*;
+
+pub mod remove_creator;
+pub use remove_creator::*;
+
+pub mod quote_bet;
+pub use quote_bet::*;
+
+pub mod withdraw_protocol_fees;
+pub use withdraw_protocol_fees::*;
+
+pub mod withdraw_creator_fees;
+pub use withdraw_creator_fees::*;
+
+pub mod register_referrer;
+pub use register_referrer::*;
+
+pub mod claim_referral_rewards;
+pub use claim_referral_rewards::*;
+
+pub mod propose_resolution;
+pub use propose_resolution::*;
+
+pub mod dispute_resolution;
+pub use dispute_resolution::*;
+
+pub mod finalize_resolution;
+pub use finalize_resolution::*;
+
+pub mod arbitrate_resolution;
+pub use arbitrate_resolution::*;
+
+pub mod expire_resolution;
+pub use expire_resolution::*;
+
+pub mod migrate_legacy_market;
+pub use migrate_legacy_market::*;
+
+pub mod migrate_legacy_bet;
+pub use migrate_legacy_bet::*;
+
+pub mod initialize_manual_oracle;
+pub use initialize_manual_oracle::*;
+
+pub mod update_manual_oracle;
+pub use update_manual_oracle::*;
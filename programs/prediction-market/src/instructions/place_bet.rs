@@ -1,74 +1,180 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::*;
+use anchor_spl::token_interface::*;
 use num_traits::*;
 
 use crate::constants::*;
+use crate::events::BetPlaced;
+use crate::instructions::build_bet_quote;
+use crate::math;
 use crate::states::*;
+use crate::utils::{deposit_collateral, received_amount};
 use crate::MarketError;
-use crate::utils::hash_to_bytes;
 
 pub fn _place_bet(
     ctx: Context<PlaceBet>,
     bet_amount:u64,
     bet_direction: Direction,
 ) -> Result<()> {
-    let market = &mut ctx.accounts.market;
+    let market = &ctx.accounts.market;
+    let clock = Clock::get()?;
 
     require!(market.initialization == MarketInitialization::InitializedPools, MarketError::InvalidMarketInitialization);
+    require!(!ctx.accounts.config.pause.betting && !market.pause.betting, MarketError::ActionPaused);
+    require_gte!(market.expiry()?, clock.slot, MarketError::MarketDurationOver);
+
+    //odds are taken from the pools before this bet is added to them
+    let quote = build_bet_quote(
+        &ctx.accounts.config,
+        market,
+        ctx.accounts.higher_pool.amount,
+        ctx.accounts.lower_pool.amount,
+        bet_amount,
+        &bet_direction,
+        clock.slot,
+    )?;
 
-    let bet_pool: AccountInfo = match bet_direction {
-        Direction::Higher => ctx.accounts.higher_pool.to_account_info(),
-        Direction::Lower => ctx.accounts.lower_pool.to_account_info()
+    let (bet_pool, pool_balance): (AccountInfo, u64) = match bet_direction {
+        Direction::Higher => (ctx.accounts.higher_pool.to_account_info(), ctx.accounts.higher_pool.amount),
+        Direction::Lower => (ctx.accounts.lower_pool.to_account_info(), ctx.accounts.lower_pool.amount)
     };
+    //wrapped SOL bets without a token account are paid from the lamports of the user
+    let user_ata = ctx.accounts.user_ata.as_ref().map(|user_ata| user_ata.to_account_info());
+    let pool_deposit = math::checked_add(math::checked_add(quote.stake, quote.lp_fee)?, quote.dynamic_fee)?;
 
-    transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.user_ata.to_account_info(),
-                to: bet_pool,
-                authority: ctx.accounts.user.to_account_info(),
-            },
-            &[&[
-            market.creator.key().as_ref(), 
-            &hash_to_bytes(&market.feed_id),
-            &market.target_price.to_le_bytes(), 
-            &market.market_duration.to_le_bytes(),
-            &[ctx.accounts.market.bump],
-        ]],
-        ),
-        bet_amount,
+    //the lp and dynamic fees stay in the pool and accrue to liquidity providers
+    deposit_collateral(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.mint,
+        ctx.accounts.user.to_account_info(),
+        user_ata.clone(),
+        bet_pool,
+        pool_deposit,
     )?;
 
-    let higher_pool_amount = ctx.accounts.higher_pool.amount;
-    let lower_pool_amount = ctx.accounts.lower_pool.amount;
-    let odds = match bet_direction {
-        Direction::Higher => {
-            if lower_pool_amount == 0 {
-                ODDS_FIXED_POINT_MULTIPLIER // 1.0 in fixed-point representation
-            } else {
-                (higher_pool_amount * ODDS_FIXED_POINT_MULTIPLIER) / lower_pool_amount
+    //a transfer fee withheld on the way to the pool is taken from the stake backing the payout
+    let pool_received = match bet_direction {
+        Direction::Higher => received_amount(&mut ctx.accounts.higher_pool, pool_balance)?,
+        Direction::Lower => received_amount(&mut ctx.accounts.lower_pool, pool_balance)?,
+    };
+    let stake = quote
+        .stake
+        .checked_sub(math::checked_sub(pool_deposit, pool_received)?)
+        .ok_or(MarketError::BetTooSmallForFees)?;
+    let payout = math::payout(stake, quote.odds)?;
+
+    //a referred bet routes the referrer's share of the protocol fee to the referral vault
+    let mut referrer = None;
+    let mut referral_share = 0;
+    let mut referral_fee = 0;
+    match (&mut ctx.accounts.referrer_stats, &mut ctx.accounts.referral_vault) {
+        (Some(referrer_stats), Some(referral_vault)) => {
+            require_keys_neq!(referrer_stats.referrer, ctx.accounts.user.key(), MarketError::SelfReferral);
+
+            referral_share = math::mul_div_floor(
+                quote.protocol_fee,
+                ctx.accounts.config.referral_share_bps as u64,
+                BPS_DENOMINATOR,
+            )?;
+
+            if referral_share > 0 {
+                let vault_balance = referral_vault.amount;
+                deposit_collateral(
+                    ctx.accounts.token_program.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                    &ctx.accounts.mint,
+                    ctx.accounts.user.to_account_info(),
+                    user_ata.clone(),
+                    referral_vault.to_account_info(),
+                    referral_share,
+                )?;
+
+                //the referrer can only claim what reached the shared referral vault
+                referral_fee = received_amount(referral_vault, vault_balance)?;
             }
+
+            referrer_stats.referred_bets = math::checked_add(referrer_stats.referred_bets, 1)?;
+            referrer_stats.referred_volume = math::checked_add(referrer_stats.referred_volume, bet_amount)?;
+            referrer_stats.total_rewards = math::checked_add(referrer_stats.total_rewards, referral_fee)?;
+            referrer_stats.unclaimed_rewards = math::checked_add(referrer_stats.unclaimed_rewards, referral_fee)?;
+
+            referrer = Some(referrer_stats.referrer);
+        }
+        (None, None) => {}
+        _ => return err!(MarketError::IncompleteReferralAccounts),
+    }
+
+    let protocol_fee = math::checked_sub(quote.protocol_fee, referral_share)?;
+    if protocol_fee > 0 {
+        deposit_collateral(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.mint,
+            ctx.accounts.user.to_account_info(),
+            user_ata.clone(),
+            ctx.accounts.protocol_fee_vault.to_account_info(),
+            protocol_fee,
+        )?;
+    }
+
+    if quote.creator_fee > 0 {
+        deposit_collateral(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.mint,
+            ctx.accounts.user.to_account_info(),
+            user_ata.clone(),
+            ctx.accounts.creator_fee_vault.to_account_info(),
+            quote.creator_fee,
+        )?;
+    }
+
+    let market = &mut ctx.accounts.market;
+    match bet_direction {
+        Direction::Higher => {
+            market.higher_liability = math::checked_add(market.higher_liability, payout)?;
+            market.higher_stake = math::checked_add(market.higher_stake, stake)?;
         }
         Direction::Lower => {
-            if higher_pool_amount == 0 {
-                ODDS_FIXED_POINT_MULTIPLIER // 1.0 in fixed-point representation
-            } else {
-                (lower_pool_amount * ODDS_FIXED_POINT_MULTIPLIER) / higher_pool_amount
-            }
+            market.lower_liability = math::checked_add(market.lower_liability, payout)?;
+            market.lower_stake = math::checked_add(market.lower_stake, stake)?;
         }
-    };
+    }
 
     let bet = &mut ctx.accounts.bet;
     bet.user = ctx.accounts.user.key();
     bet.bump = ctx.bumps.bet;
     bet.amount = bet_amount;
-    bet.odds =odds;
+    bet.stake = stake;
+    bet.odds = quote.odds;
+    bet.protocol_fee = quote.protocol_fee;
+    bet.creator_fee = quote.creator_fee;
+    bet.lp_fee = quote.lp_fee;
+    bet.dynamic_fee_bps = quote.dynamic_fee_bps;
+    bet.dynamic_fee = quote.dynamic_fee;
+    bet.referrer = referrer;
+    bet.referral_fee = referral_fee;
     bet.claimed = false;
     bet.market = ctx.accounts.market.key();
-    bet.direction = bet_direction;
+    bet.direction = bet_direction.clone();
     bet.initialized = true;
 
+    emit!(BetPlaced {
+        market: bet.market,
+        user: bet.user,
+        direction: bet_direction,
+        amount: bet_amount,
+        stake,
+        odds: quote.odds,
+        protocol_fee: quote.protocol_fee,
+        creator_fee: quote.creator_fee,
+        lp_fee: quote.lp_fee,
+        dynamic_fee_bps: quote.dynamic_fee_bps,
+        dynamic_fee: quote.dynamic_fee,
+        referrer,
+        referral_fee,
+    });
+
     Ok(())
 }
 
@@ -79,13 +185,19 @@ pub struct PlaceBet<'info> {
         mut,
         seeds = [
             market.creator.key().as_ref(), 
-            &hash_to_bytes(&market.feed_id),
+            &market.feed_seed,
             &market.target_price.to_le_bytes(), 
             &market.market_duration.to_le_bytes(),
         ],
         bump = market.bump,
     )]
-    pub market: Account<'info, Market>,
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
 
     #[account(
         mut,
@@ -97,7 +209,7 @@ pub struct PlaceBet<'info> {
         ],
         bump = market.higher_pool_bump,
     )]
-    pub higher_pool: Account<'info, TokenAccount>,
+    pub higher_pool: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
@@ -109,14 +221,58 @@ pub struct PlaceBet<'info> {
         ],
         bump = market.lower_pool_bump,
     )]
-    pub lower_pool: Account<'info, TokenAccount>,
+    pub lower_pool: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    //optional for wrapped SOL markets, the bet is then paid in lamports
     #[account(
         mut,
         associated_token::mint = market.mint,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [
+            PROTOCOL_FEE_VAULT_SEED.as_bytes(),
+            market.mint.as_ref(),
+        ],
+        bump,
+    )]
+    pub protocol_fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            CREATOR_FEE_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.creator_fee_vault_bump,
+    )]
+    pub creator_fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //optional, present when the bet comes through a registered referrer
+    #[account(
+        mut,
+        seeds = [
+            REFERRER_SEED.as_bytes(),
+            referrer_stats.referrer.as_ref(),
+            market.mint.as_ref(),
+        ],
+        bump = referrer_stats.bump,
     )]
-    pub user_ata: Account<'info, TokenAccount>,
+    pub referrer_stats: Option<Box<Account<'info, ReferrerStats>>>,
+
+    #[account(
+        mut,
+        seeds = [
+            REFERRAL_VAULT_SEED.as_bytes(),
+            market.mint.as_ref(),
+        ],
+        bump,
+    )]
+    pub referral_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(mut)]
     pub user: Signer<'info>,
@@ -134,8 +290,11 @@ pub struct PlaceBet<'info> {
         ], // I realize that a users may need to place the same exact bet multiple using a Bet Id might solve that
         bump
     )]
-    pub bet: Account<'info,Bet>,
+    pub bet: Box<Account<'info,Bet>>,
+
+    #[account(address = market.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::events::AdminProposed;
+use crate::states::*;
+use crate::MarketError;
+
+//first step of an admin transfer, the admin only changes once `new_admin` accepts
+pub fn _propose_admin(
+    ctx: Context<ProposeAdmin>,
+    new_admin: Pubkey,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.pending_admin = Some(new_admin);
+
+    emit!(AdminProposed {
+        admin: config.admin,
+        pending_admin: new_admin,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
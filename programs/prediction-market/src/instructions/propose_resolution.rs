@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::events::ResolutionProposed;
+use crate::states::*;
+use crate::MarketError;
+
+//posts a final price backed by the market bond, it settles the market unless disputed
+//within the challenge window
+pub fn _propose_resolution(
+    ctx: Context<ProposeResolution>,
+    price: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    require!(market.initialization == MarketInitialization::InitializedPools,MarketError::InvalidMarketInitialization);
+    require!(!ctx.accounts.config.pause.claiming && !market.pause.claiming,MarketError::ActionPaused);
+    require_gt!(clock.slot,market.expiry()?,MarketError::MarketDurationNotOver);
+    require!(market.kind == MarketKind::Price,MarketError::InvalidMarketKind);
+    require!(!market.is_settled(),MarketError::MarketAlreadySettled);
+    require!(!market.voided,MarketError::MarketVoided);
+
+    let bond = market
+        .optimistic_resolution
+        .as_ref()
+        .ok_or(MarketError::OptimisticResolutionDisabled)?
+        .bond;
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.proposer_ata.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.resolution_vault.to_account_info(),
+                authority: ctx.accounts.proposer.to_account_info(),
+            },
+        ),
+        bond,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    market.resolution_pending = true;
+
+    let resolution = &mut ctx.accounts.resolution;
+    resolution.market = market.key();
+    resolution.proposer = ctx.accounts.proposer.key();
+    resolution.proposed_price = price;
+    resolution.proposed_at = clock.slot;
+    resolution.bond = bond;
+    resolution.status = ResolutionStatus::Proposed;
+    resolution.bump = ctx.bumps.resolution;
+    resolution.vault_bump = ctx.bumps.resolution_vault;
+    resolution.paused_slots_at_proposal = market.paused_slots(&ctx.accounts.config, clock.slot);
+
+    emit!(ResolutionProposed {
+        market: market.key(),
+        proposer: resolution.proposer,
+        price,
+        bond,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeResolution<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Resolution::INIT_SPACE,
+        seeds = [
+            RESOLUTION_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump
+    )]
+    pub resolution: Box<Account<'info, Resolution>>,
+
+    #[account(
+        init,
+        payer = proposer,
+        token::mint = mint,
+        token::authority = market,
+        seeds = [
+            RESOLUTION_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump
+    )]
+    pub resolution_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = market.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = proposer,
+        associated_token::token_program = token_program,
+    )]
+    pub proposer_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::constants::*;
+use crate::math;
+use crate::states::*;
+use crate::MarketError;
+
+//splits a bet into its fees and the stake that backs the payout, shared with place_bet
+pub fn build_bet_quote(
+    config: &Config,
+    market: &Market,
+    higher_pool_amount: u64,
+    lower_pool_amount: u64,
+    bet_amount: u64,
+    bet_direction: &Direction,
+    slot: u64,
+) -> Result<BetQuote> {
+    require!(
+        (market.min_bet..=market.max_bet).contains(&bet_amount),
+        MarketError::BetOutOfRange
+    );
+
+    let protocol_fee = math::fee(bet_amount, config.protocol_fee_bps as u64)?;
+    let creator_fee = math::fee(bet_amount, market.creator_fee_bps as u64)?;
+    let lp_fee = math::fee(bet_amount, config.lp_fee_bps as u64)?;
+
+    let dynamic_fee_bps = match &market.fee_curve {
+        Some(curve) => math::time_decay_fee_bps(
+            curve.fee_min_bps,
+            curve.fee_max_bps,
+            curve.ramp_bps,
+            market.market_duration,
+            slot.saturating_sub(market.start_time),
+        )?,
+        None => 0,
+    };
+    let dynamic_fee = math::fee(bet_amount, dynamic_fee_bps)?;
+
+    let total_fee = math::checked_add(
+        math::checked_add(protocol_fee, creator_fee)?,
+        math::checked_add(lp_fee, dynamic_fee)?,
+    )?;
+    require_gt!(bet_amount, total_fee, MarketError::BetTooSmallForFees);
+    let stake = math::checked_sub(bet_amount, total_fee)?;
+
+    let odds = match bet_direction {
+        Direction::Higher => math::odds(higher_pool_amount, lower_pool_amount)?,
+        Direction::Lower => math::odds(lower_pool_amount, higher_pool_amount)?,
+    };
+
+    Ok(BetQuote {
+        stake,
+        odds,
+        payout: math::payout(stake, odds)?,
+        protocol_fee,
+        creator_fee,
+        lp_fee,
+        dynamic_fee_bps: dynamic_fee_bps as u16,
+        dynamic_fee,
+    })
+}
+
+pub fn _quote_bet(
+    ctx: Context<QuoteBet>,
+    bet_amount: u64,
+    bet_direction: Direction,
+) -> Result<BetQuote> {
+    let market = &ctx.accounts.market;
+
+    require!(market.initialization == MarketInitialization::InitializedPools, MarketError::InvalidMarketInitialization);
+
+    build_bet_quote(
+        &ctx.accounts.config,
+        market,
+        ctx.accounts.higher_pool.amount,
+        ctx.accounts.lower_pool.amount,
+        bet_amount,
+        &bet_direction,
+        Clock::get()?.slot,
+    )
+}
+
+#[derive(Accounts)]
+pub struct QuoteBet<'info> {
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [
+            HIGHER_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_pool_bump,
+    )]
+    pub higher_pool: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [
+            LOWER_POOL_SEED.as_bytes(),
+            market.key().as_ref()
+        ],
+        bump = market.lower_pool_bump,
+    )]
+    pub lower_pool: InterfaceAccount<'info, TokenAccount>,
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+
+//lets `creator` create markets within its quota, required when the config is permissioned
+pub fn _register_creator(
+    ctx: Context<RegisterCreator>,
+    creator: Pubkey,
+    params: CreatorParams,
+) -> Result<()> {
+    let creator_registry = &mut ctx.accounts.creator_registry;
+
+    creator_registry.creator = creator;
+    creator_registry.bump = ctx.bumps.creator_registry;
+    creator_registry.rent_payer = ctx.accounts.payer.key();
+    creator_registry.apply(&params)
+}
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct RegisterCreator<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CreatorRegistry::INIT_SPACE,
+        seeds = [
+            CREATOR_REGISTRY_SEED.as_bytes(),
+            creator.as_ref(),
+        ],
+        bump
+    )]
+    pub creator_registry: Account<'info, CreatorRegistry>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
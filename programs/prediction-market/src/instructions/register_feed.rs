@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+
+//adds a feed to the registry, markets can only be created on registered and enabled feeds
+pub fn _register_feed(
+    ctx: Context<RegisterFeed>,
+    feed_id: [u8; 32],
+    params: FeedParams,
+) -> Result<()> {
+    let feed_registry = &mut ctx.accounts.feed_registry;
+
+    feed_registry.feed_id = feed_id;
+    feed_registry.bump = ctx.bumps.feed_registry;
+    feed_registry.apply(&params, &ctx.accounts.config)
+}
+
+#[derive(Accounts)]
+#[instruction(feed_id: [u8; 32])]
+pub struct RegisterFeed<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + FeedRegistry::INIT_SPACE,
+        seeds = [
+            FEED_REGISTRY_SEED.as_bytes(),
+            feed_id.as_ref(),
+        ],
+        bump
+    )]
+    pub feed_registry: Account<'info, FeedRegistry>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, Config>,
+
+    //may be a PDA of a multisig program, rent is paid by a separate signer
+    pub admin: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::states::*;
+
+//opens the referral stats of a partner for one collateral mint
+pub fn _register_referrer(
+    ctx: Context<RegisterReferrer>,
+) -> Result<()> {
+    let referrer_stats = &mut ctx.accounts.referrer_stats;
+
+    referrer_stats.referrer = ctx.accounts.referrer.key();
+    referrer_stats.mint = ctx.accounts.mint.key();
+    referrer_stats.bump = ctx.bumps.referrer_stats;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(
+        init,
+        payer = referrer,
+        space = 8 + ReferrerStats::INIT_SPACE,
+        seeds = [
+            REFERRER_SEED.as_bytes(),
+            referrer.key().as_ref(),
+            mint.key().as_ref(),
+        ],
+        bump
+    )]
+    pub referrer_stats: Account<'info, ReferrerStats>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    //referral rewards of every referrer using this mint are held here
+    #[account(
+        init_if_needed,
+        payer = referrer,
+        token::mint = mint,
+        token::authority = config,
+        seeds = [
+            REFERRAL_VAULT_SEED.as_bytes(),
+            mint.key().as_ref(),
+        ],
+        bump
+    )]
+    pub referral_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
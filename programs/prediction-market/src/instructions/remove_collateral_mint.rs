@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+
+//stops new markets from using the mint, markets already backed by it keep their bet limits
+pub fn _remove_collateral_mint(
+    _ctx: Context<RemoveCollateralMint>,
+) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveCollateralMint<'info> {
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [
+            COLLATERAL_CONFIG_SEED.as_bytes(),
+            collateral_config.mint.as_ref(),
+        ],
+        bump = collateral_config.bump,
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    /// CHECK: only receives the rent of the closed account
+    #[account(
+        mut,
+        address = collateral_config.rent_payer @ MarketError::InvalidRentPayer,
+    )]
+    pub rent_payer: UncheckedAccount<'info>,
+}
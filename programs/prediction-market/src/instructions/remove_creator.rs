@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+
+//removes a creator whose markets are all closed, set its quota to 0 to stop new markets sooner
+pub fn _remove_creator(
+    _ctx: Context<RemoveCreator>,
+) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveCreator<'info> {
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [
+            CREATOR_REGISTRY_SEED.as_bytes(),
+            creator_registry.creator.as_ref(),
+        ],
+        bump = creator_registry.bump,
+        constraint = creator_registry.open_markets == 0 @ MarketError::CreatorHasOpenMarkets,
+    )]
+    pub creator_registry: Account<'info, CreatorRegistry>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    /// CHECK: only receives the rent of the closed account
+    #[account(
+        mut,
+        address = creator_registry.rent_payer @ MarketError::InvalidRentPayer,
+    )]
+    pub rent_payer: UncheckedAccount<'info>,
+}
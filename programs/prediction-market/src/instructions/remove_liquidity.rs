@@ -0,0 +1,184 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::math;
+use crate::states::*;
+use crate::MarketError;
+
+//burns `lp_amount` LP tokens for a pro-rata share of what remains in each pool once
+//the creator seed and the payouts or refunds still owed to bettors are set aside, once every
+//LP has left the rest stays in the pools for finalize_market to sweep to the creator
+pub fn _remove_liquidity(
+    ctx: Context<RemoveLiquidity>,
+    lp_amount: u64,
+) -> Result<()> {
+    let market = &ctx.accounts.market;
+    let higher_pool = &ctx.accounts.higher_pool;
+    let lower_pool = &ctx.accounts.lower_pool;
+    let lp_supply = ctx.accounts.lp_mint.supply;
+
+    require!(market.initialization == MarketInitialization::InitializedPools, MarketError::InvalidMarketInitialization);
+    require!(!ctx.accounts.config.pause.claiming && !market.pause.claiming, MarketError::ActionPaused);
+    require!(market.is_settled() || market.voided, MarketError::MarketNotSettled);
+    require_gt!(lp_amount, 0, MarketError::ZeroLiquidity);
+
+    let winning_direction = market.winning_direction();
+
+    let mut higher_reserved = market.seed_amount;
+    let mut lower_reserved = market.seed_amount;
+    match winning_direction {
+        Some(Direction::Higher) => higher_reserved = math::checked_add(higher_reserved, market.higher_liability)?,
+        Some(Direction::Lower) => lower_reserved = math::checked_add(lower_reserved, market.lower_liability)?,
+        None => {}
+    }
+    if market.voided {
+        higher_reserved = math::checked_add(higher_reserved, market.higher_stake)?;
+        lower_reserved = math::checked_add(lower_reserved, market.lower_stake)?;
+    }
+
+    let higher_share = math::mul_div_floor(
+        higher_pool.amount.saturating_sub(higher_reserved),
+        lp_amount,
+        lp_supply,
+    )?;
+    let lower_share = math::mul_div_floor(
+        lower_pool.amount.saturating_sub(lower_reserved),
+        lp_amount,
+        lp_supply,
+    )?;
+
+    burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                from: ctx.accounts.provider_lp_ata.to_account_info(),
+                authority: ctx.accounts.provider.to_account_info(),
+            },
+        ),
+        lp_amount,
+    )?;
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: higher_pool.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.provider_ata.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            &[&[
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+            &[market.bump],
+        ]],
+        ),
+        higher_share,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: lower_pool.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.provider_ata.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            &[&[
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+            &[market.bump],
+        ]],
+        ),
+        lower_share,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveLiquidity<'info> {
+    #[account(
+        seeds = [
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        seeds = [
+            HIGHER_POOL_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.higher_pool_bump,
+    )]
+    pub higher_pool: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        seeds = [
+            LOWER_POOL_SEED.as_bytes(),
+            market.key().as_ref()
+        ],
+        bump = market.lower_pool_bump,
+    )]
+    pub lower_pool: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            LP_MINT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.lp_mint_bump,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program,
+    )]
+    pub provider_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = provider,
+        associated_token::token_program = token_program,
+    )]
+    pub provider_lp_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub provider: Signer<'info>,
+
+    #[account(address = market.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
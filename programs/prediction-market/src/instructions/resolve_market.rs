@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::events::EventResolved;
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+
+//the resolver of an event market records whether the event happened
+pub fn _resolve_market(
+    ctx: Context<ResolveMarket>,
+    outcome: bool,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    require!(market.kind == MarketKind::Event,MarketError::InvalidMarketKind);
+    require!(market.initialization == MarketInitialization::InitializedPools,MarketError::InvalidMarketInitialization);
+    require!(!ctx.accounts.config.pause.claiming && !market.pause.claiming,MarketError::ActionPaused);
+    require!(market.resolver == Some(ctx.accounts.resolver.key()),MarketError::UnauthorizedUser);
+    require_gt!(clock.slot,market.expiry()?,MarketError::MarketDurationNotOver);
+    require_gte!(market.settlement_deadline(&ctx.accounts.config,clock.slot),clock.slot,MarketError::ResolutionDeadlineOver);
+    require!(!market.is_settled(),MarketError::MarketAlreadySettled);
+    require!(!market.voided,MarketError::MarketVoided);
+
+    market.event_outcome = Some(outcome);
+
+    emit!(EventResolved {
+        market: market.key(),
+        resolver: ctx.accounts.resolver.key(),
+        outcome,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ResolveMarket<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    pub resolver: Signer<'info>,
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::events::PauseChanged;
+use crate::states::*;
+
+//replaces the pause flags of one market, see Config::check_pause_authority
+pub fn _set_market_pause(
+    ctx: Context<SetMarketPause>,
+    pause: PauseFlags,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let authority = ctx.accounts.authority.key();
+    ctx.accounts.config.check_pause_authority(&authority, &market.pause, &pause)?;
+
+    market.claiming_paused.update(pause.claiming, Clock::get()?.slot);
+    market.pause = pause.clone();
+
+    emit!(PauseChanged {
+        market: Some(market.key()),
+        authority,
+        pause,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetMarketPause<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::events::PauseChanged;
+use crate::states::*;
+
+//replaces the protocol-wide pause flags, see Config::check_pause_authority
+pub fn _set_protocol_pause(
+    ctx: Context<SetProtocolPause>,
+    pause: PauseFlags,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let authority = ctx.accounts.authority.key();
+    config.check_pause_authority(&authority, &config.pause, &pause)?;
+
+    config.claiming_paused.update(pause.claiming, Clock::get()?.slot);
+    config.pause = pause.clone();
+
+    emit!(PauseChanged {
+        market: None,
+        authority,
+        pause,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetProtocolPause<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub authority: Signer<'info>,
+}
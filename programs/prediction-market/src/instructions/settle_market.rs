@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+use crate::oracle::settle_final_price;
+
+//permissionless settlement so the final price can be recorded without waiting for a claim,
+//price accounts of the extra feeds of the market are passed as remaining accounts
+pub fn _settle_market<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleMarket<'info>>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    require!(market.initialization == MarketInitialization::InitializedPools,MarketError::InvalidMarketInitialization);
+    require!(!ctx.accounts.config.pause.claiming && !market.pause.claiming,MarketError::ActionPaused);
+    require_gt!(clock.slot,market.expiry()?,MarketError::MarketDurationNotOver);
+    require!(market.kind == MarketKind::Price,MarketError::InvalidMarketKind);
+    require!(!market.is_settled(),MarketError::MarketAlreadySettled);
+    require!(!market.voided,MarketError::MarketVoided);
+    require_gte!(market.settlement_deadline(&ctx.accounts.config,clock.slot),clock.slot,MarketError::SettlementWindowOver);
+
+    settle_final_price(market, &ctx.accounts.price_update.to_account_info(), ctx.remaining_accounts, &clock)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleMarket<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    /// CHECK: owner, layout and feed are validated by the oracle adapter of the market
+    pub price_update: UncheckedAccount<'info>,
+}
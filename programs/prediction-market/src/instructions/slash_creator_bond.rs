@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::events::CreatorBondSlashed;
+use crate::states::*;
+use crate::utils::release_bond_vault;
+use crate::MarketError;
+
+//anyone can move the bond of a market that was neither settled nor voided by its slash deadline,
+//or that was voided because no price arrived, into the protocol fee vault. The caller receives the
+//rent of the bond vault
+pub fn _slash_creator_bond(
+    ctx: Context<SlashCreatorBond>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    //settlement is stopped by the claiming flag, the creator can not be blamed for it meanwhile
+    require!(!ctx.accounts.config.pause.claiming && !market.pause.claiming, MarketError::ActionPaused);
+    require!(market.bond_vault_open, MarketError::BondNotSlashable);
+    let unresolved = !market.is_settled() && !market.voided;
+    require!(unresolved || market.voided_by_timeout, MarketError::BondNotSlashable);
+    require_gt!(clock.slot, market.bond_slash_deadline(&ctx.accounts.config, clock.slot), MarketError::BondNotSlashable);
+
+    let amount = ctx.accounts.bond_vault.amount;
+    release_bond_vault(
+        market,
+        &ctx.accounts.token_program,
+        &ctx.accounts.bond_vault,
+        &ctx.accounts.mint,
+        &ctx.accounts.protocol_fee_vault,
+        &ctx.accounts.slasher.to_account_info(),
+    )?;
+    market.bond_vault_open = false;
+
+    emit!(CreatorBondSlashed {
+        market: market.key(),
+        creator: market.creator,
+        slasher: ctx.accounts.slasher.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SlashCreatorBond<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        seeds = [
+            BOND_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.bond_vault_bump,
+    )]
+    pub bond_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //markets that never initialized their pools may be the first on their mint
+    #[account(
+        init_if_needed,
+        payer = slasher,
+        token::mint = mint,
+        token::authority = config,
+        seeds = [
+            PROTOCOL_FEE_VAULT_SEED.as_bytes(),
+            market.mint.as_ref(),
+        ],
+        bump,
+    )]
+    pub protocol_fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = market.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub slasher: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+
+pub fn _update_config(
+    ctx: Context<UpdateConfig>,
+    params: ConfigParams,
+) -> Result<()> {
+    ctx.accounts.config.apply(&params)
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+
+//changes the limits of a creator, markets already open stay open even above a lowered quota
+pub fn _update_creator(
+    ctx: Context<UpdateCreator>,
+    params: CreatorParams,
+) -> Result<()> {
+    ctx.accounts.creator_registry.apply(&params)
+}
+
+#[derive(Accounts)]
+pub struct UpdateCreator<'info> {
+    #[account(
+        mut,
+        seeds = [
+            CREATOR_REGISTRY_SEED.as_bytes(),
+            creator_registry.creator.as_ref(),
+        ],
+        bump = creator_registry.bump,
+    )]
+    pub creator_registry: Account<'info, CreatorRegistry>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+
+//changes the settings of a registered feed, existing markets keep the values they copied
+pub fn _update_feed(
+    ctx: Context<UpdateFeed>,
+    params: FeedParams,
+) -> Result<()> {
+    ctx.accounts.feed_registry.apply(&params, &ctx.accounts.config)
+}
+
+#[derive(Accounts)]
+pub struct UpdateFeed<'info> {
+    #[account(
+        mut,
+        seeds = [
+            FEED_REGISTRY_SEED.as_bytes(),
+            feed_registry.feed_id.as_ref(),
+        ],
+        bump = feed_registry.bump,
+    )]
+    pub feed_registry: Account<'info, FeedRegistry>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::states::ManualOracle;
+use crate::MarketError;
+
+//publishes a new price, the publish time is taken from the cluster clock
+pub fn _update_manual_oracle(
+    ctx: Context<UpdateManualOracle>,
+    price: i64,
+    conf: u64,
+) -> Result<()> {
+    require_gt!(price, 0, MarketError::NonPositivePrice);
+
+    let oracle = &mut ctx.accounts.manual_oracle;
+
+    oracle.price = price;
+    oracle.conf = conf;
+    oracle.publish_time = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateManualOracle<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            MANUAL_ORACLE_SEED.as_bytes(),
+            authority.key().as_ref(),
+            manual_oracle.feed_id.as_ref(),
+        ],
+        bump = manual_oracle.bump,
+    )]
+    pub manual_oracle: Account<'info, ManualOracle>,
+
+    pub authority: Signer<'info>,
+}
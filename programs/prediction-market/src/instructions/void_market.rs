@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::events::MarketVoided;
+use crate::constants::*;
+use crate::states::*;
+use crate::MarketError;
+
+//permissionless fallback once the settlement window passes without an acceptable price or
+//an event outcome, bets of a voided market are refunded their stake on claim
+pub fn _void_market(
+    ctx: Context<VoidMarket>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    require!(market.initialization == MarketInitialization::InitializedPools,MarketError::InvalidMarketInitialization);
+    require!(!ctx.accounts.config.pause.claiming && !market.pause.claiming,MarketError::ActionPaused);
+    require!(!market.is_settled(),MarketError::MarketAlreadySettled);
+    require!(!market.voided,MarketError::MarketVoided);
+    require!(!market.resolution_pending,MarketError::ResolutionPending);
+    require_gt!(clock.slot,market.settlement_deadline(&ctx.accounts.config,clock.slot),MarketError::SettlementWindowNotOver);
+
+    market.voided = true;
+    market.voided_by_timeout = true;
+
+    emit!(MarketVoided {
+        market: market.key(),
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct VoidMarket<'info> {
+    #[account(
+        mut,
+        seeds = [
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::events::CreatorFeesWithdrawn;
+use crate::states::*;
+use crate::MarketError;
+
+pub fn _withdraw_creator_fees(
+    ctx: Context<WithdrawCreatorFees>,
+) -> Result<()> {
+    let market = &ctx.accounts.market;
+    let amount = ctx.accounts.creator_fee_vault.amount;
+
+    require_keys_eq!(ctx.accounts.market_creator.key(),market.creator,MarketError::UnauthorizedUser);
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.creator_fee_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.creator_ata.to_account_info(),
+                authority: market.to_account_info(),
+            },
+            &[&[
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+            &[market.bump],
+        ]],
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    emit!(CreatorFeesWithdrawn {
+        market: market.key(),
+        creator: market.creator,
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawCreatorFees<'info> {
+    #[account(
+        seeds = [
+            market.creator.key().as_ref(),
+            &market.feed_seed,
+            &market.target_price.to_le_bytes(),
+            &market.market_duration.to_le_bytes(),
+        ],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        token::mint = market.mint,
+        token::authority = market,
+        seeds = [
+            CREATOR_FEE_VAULT_SEED.as_bytes(),
+            market.key().as_ref(),
+        ],
+        bump = market.creator_fee_vault_bump,
+    )]
+    pub creator_fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = market.mint,
+        associated_token::authority = market_creator,
+        associated_token::token_program = token_program,
+    )]
+    pub creator_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        address = market.creator,
+    )]
+    pub market_creator: Signer<'info>,
+
+    #[account(address = market.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
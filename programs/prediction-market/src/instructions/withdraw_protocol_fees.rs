@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::*;
+
+use crate::constants::*;
+use crate::events::ProtocolFeesWithdrawn;
+use crate::states::*;
+use crate::MarketError;
+
+//sweeps the protocol fee vault of one mint into a token account chosen by the admin
+pub fn _withdraw_protocol_fees(
+    ctx: Context<WithdrawProtocolFees>,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let amount = ctx.accounts.protocol_fee_vault.amount;
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.protocol_fee_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: config.to_account_info(),
+            },
+            &[&[CONFIG_SEED.as_bytes(), &[config.bump]]],
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    emit!(ProtocolFeesWithdrawn {
+        mint: ctx.accounts.mint.key(),
+        destination: ctx.accounts.destination.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawProtocolFees<'info> {
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ MarketError::UnauthorizedUser,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = config,
+        seeds = [
+            PROTOCOL_FEE_VAULT_SEED.as_bytes(),
+            mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub protocol_fee_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -2,12 +2,16 @@ use anchor_lang::prelude::*;
 
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
+pub mod math;
+pub mod oracle;
 pub mod state;
 pub mod utils;
 
 pub use constants::*;
 pub use error::*;
+pub use events::*;
 pub use instructions::*;
 pub use state::*;
 pub use utils::*;
@@ -18,13 +22,91 @@ declare_id!("4g6WvpafKJy1kbJjtA1zkusnqcMbCFiF7fdnDKZW6jZk");
 pub mod prediction_market {
     use super::*;
 
-    pub fn initialize_market(
-        ctx: Context<InitializeMarket>,
+    pub fn initialize_config(ctx: Context<InitializeConfig>, params: ConfigParams) -> Result<()> {
+        _initialize_config(ctx, params)
+    }
+
+    pub fn update_config(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+        _update_config(ctx, params)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        _propose_admin(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        _accept_admin(ctx)
+    }
+
+    pub fn set_protocol_pause(ctx: Context<SetProtocolPause>, pause: PauseFlags) -> Result<()> {
+        _set_protocol_pause(ctx, pause)
+    }
+
+    pub fn set_market_pause(ctx: Context<SetMarketPause>, pause: PauseFlags) -> Result<()> {
+        _set_market_pause(ctx, pause)
+    }
+
+    pub fn register_feed(
+        ctx: Context<RegisterFeed>,
+        feed_id: [u8; 32],
+        params: FeedParams,
+    ) -> Result<()> {
+        _register_feed(ctx, feed_id, params)
+    }
+
+    pub fn update_feed(ctx: Context<UpdateFeed>, params: FeedParams) -> Result<()> {
+        _update_feed(ctx, params)
+    }
+
+    pub fn add_collateral_mint(
+        ctx: Context<AddCollateralMint>,
+        params: CollateralParams,
+    ) -> Result<()> {
+        _add_collateral_mint(ctx, params)
+    }
+
+    pub fn remove_collateral_mint(ctx: Context<RemoveCollateralMint>) -> Result<()> {
+        _remove_collateral_mint(ctx)
+    }
+
+    pub fn register_creator(
+        ctx: Context<RegisterCreator>,
+        creator: Pubkey,
+        params: CreatorParams,
+    ) -> Result<()> {
+        _register_creator(ctx, creator, params)
+    }
+
+    pub fn update_creator(ctx: Context<UpdateCreator>, params: CreatorParams) -> Result<()> {
+        _update_creator(ctx, params)
+    }
+
+    pub fn remove_creator(ctx: Context<RemoveCreator>) -> Result<()> {
+        _remove_creator(ctx)
+    }
+
+    pub fn initialize_market<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitializeMarket<'info>>,
         taget_price: u64,
-        feed_id: String, // from https://pyth.network/developers/price-feed-ids#solana-stables
+        feed_id: Vec<u8>, // from https://pyth.network/developers/price-feed-ids#solana-stables
         market_duration: u64,
+        params: MarketParams,
     ) -> Result<()> {
-        _initialize_market(ctx, taget_price, feed_id, market_duration)
+        _initialize_market(ctx, taget_price, feed_id, market_duration, params)
+    }
+
+    pub fn initialize_event_market(
+        ctx: Context<InitializeEventMarket>,
+        event_id: [u8; 32],
+        market_duration: u64,
+        resolver: Pubkey,
+        params: MarketParams,
+    ) -> Result<()> {
+        _initialize_event_market(ctx, event_id, market_duration, resolver, params)
+    }
+
+    pub fn resolve_market(ctx: Context<ResolveMarket>, outcome: bool) -> Result<()> {
+        _resolve_market(ctx, outcome)
     }
 
     pub fn initialize_pools(ctx: Context<InitializePools>) -> Result<()> {
@@ -47,7 +129,95 @@ pub mod prediction_market {
         _place_bet(ctx, bet_amount, bet_direction)
     }
 
-    pub fn claim_bet(ctx: Context<ClaimBet>) -> Result<()> {
+    pub fn claim_bet<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimBet<'info>>) -> Result<()> {
         _claim_bet(ctx)
     }
+
+    pub fn settle_market<'info>(ctx: Context<'_, '_, 'info, 'info, SettleMarket<'info>>) -> Result<()> {
+        _settle_market(ctx)
+    }
+
+    pub fn void_market(ctx: Context<VoidMarket>) -> Result<()> {
+        _void_market(ctx)
+    }
+
+    pub fn slash_creator_bond(ctx: Context<SlashCreatorBond>) -> Result<()> {
+        _slash_creator_bond(ctx)
+    }
+
+    pub fn add_liquidity(ctx: Context<AddLiquidity>, amount: u64) -> Result<()> {
+        _add_liquidity(ctx, amount)
+    }
+
+    pub fn remove_liquidity(ctx: Context<RemoveLiquidity>, lp_amount: u64) -> Result<()> {
+        _remove_liquidity(ctx, lp_amount)
+    }
+
+    pub fn quote_bet(
+        ctx: Context<QuoteBet>,
+        bet_amount: u64,
+        bet_direction: Direction,
+    ) -> Result<BetQuote> {
+        _quote_bet(ctx, bet_amount, bet_direction)
+    }
+
+    pub fn withdraw_protocol_fees(ctx: Context<WithdrawProtocolFees>) -> Result<()> {
+        _withdraw_protocol_fees(ctx)
+    }
+
+    pub fn withdraw_creator_fees(ctx: Context<WithdrawCreatorFees>) -> Result<()> {
+        _withdraw_creator_fees(ctx)
+    }
+
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        _register_referrer(ctx)
+    }
+
+    pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+        _claim_referral_rewards(ctx)
+    }
+
+    pub fn propose_resolution(ctx: Context<ProposeResolution>, price: u64) -> Result<()> {
+        _propose_resolution(ctx, price)
+    }
+
+    pub fn dispute_resolution(ctx: Context<DisputeResolution>) -> Result<()> {
+        _dispute_resolution(ctx)
+    }
+
+    pub fn finalize_resolution(ctx: Context<FinalizeResolution>) -> Result<()> {
+        _finalize_resolution(ctx)
+    }
+
+    pub fn arbitrate_resolution(ctx: Context<ArbitrateResolution>, price: u64) -> Result<()> {
+        _arbitrate_resolution(ctx, price)
+    }
+
+    pub fn expire_resolution(ctx: Context<ExpireResolution>) -> Result<()> {
+        _expire_resolution(ctx)
+    }
+
+    pub fn migrate_legacy_market(ctx: Context<MigrateLegacyMarket>) -> Result<()> {
+        _migrate_legacy_market(ctx)
+    }
+
+    pub fn migrate_legacy_bet(ctx: Context<MigrateLegacyBet>) -> Result<()> {
+        _migrate_legacy_bet(ctx)
+    }
+
+    pub fn initialize_manual_oracle(
+        ctx: Context<InitializeManualOracle>,
+        feed_id: [u8; 32],
+        exponent: i32,
+    ) -> Result<()> {
+        _initialize_manual_oracle(ctx, feed_id, exponent)
+    }
+
+    pub fn update_manual_oracle(
+        ctx: Context<UpdateManualOracle>,
+        price: i64,
+        conf: u64,
+    ) -> Result<()> {
+        _update_manual_oracle(ctx, price, conf)
+    }
 }
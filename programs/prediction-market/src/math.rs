@@ -0,0 +1,371 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::MarketError;
+
+//Fixed-point helpers shared by every odds, payout and fee calculation.
+//Products are computed in u128 so they can not overflow, rounding is always
+//explicit: floor for amounts paid out of the pools, ceil for amounts charged.
+
+pub fn mul_div_floor(a: u64, b: u64, denominator: u64) -> Result<u64> {
+    require_neq!(denominator, 0, MarketError::MathDivisionByZero);
+
+    let result = (a as u128) * (b as u128) / (denominator as u128);
+
+    u64::try_from(result).map_err(|_| error!(MarketError::MathOverflow))
+}
+
+pub fn mul_div_ceil(a: u64, b: u64, denominator: u64) -> Result<u64> {
+    require_neq!(denominator, 0, MarketError::MathDivisionByZero);
+
+    let result = ((a as u128) * (b as u128)).div_ceil(denominator as u128);
+
+    u64::try_from(result).map_err(|_| error!(MarketError::MathOverflow))
+}
+
+//odds of a bet placed on `bet_pool_amount` against `opposite_pool_amount`,
+//an empty opposite pool is treated as even odds
+pub fn odds(bet_pool_amount: u64, opposite_pool_amount: u64) -> Result<u64> {
+    if opposite_pool_amount == 0 {
+        return Ok(ODDS_FIXED_POINT_MULTIPLIER); // 1.0 in fixed-point representation
+    }
+
+    mul_div_floor(
+        bet_pool_amount,
+        ODDS_FIXED_POINT_MULTIPLIER,
+        opposite_pool_amount,
+    )
+}
+
+//amount paid to a winning bet, rounded down so the pool never pays more than owed
+pub fn payout(bet_amount: u64, odds: u64) -> Result<u64> {
+    mul_div_floor(bet_amount, odds, ODDS_FIXED_POINT_MULTIPLIER)
+}
+
+//fee charged on `amount` at `fee_bps`, rounded up so dust never escapes the fee
+pub fn fee(amount: u64, fee_bps: u64) -> Result<u64> {
+    require_gte!(BPS_DENOMINATOR, fee_bps, MarketError::InvalidFeeBps);
+
+    mul_div_ceil(amount, fee_bps, BPS_DENOMINATOR)
+}
+
+//width of an oracle confidence interval relative to its price, rounded up and
+//saturated so an absurdly wide interval still compares above any limit
+pub fn confidence_bps(conf: u64, price: u64) -> Result<u64> {
+    require_neq!(price, 0, MarketError::MathDivisionByZero);
+
+    let bps = ((conf as u128) * (BPS_DENOMINATOR as u128)).div_ceil(price as u128);
+
+    Ok(u64::try_from(bps).unwrap_or(u64::MAX))
+}
+
+//fee in bps that stays at `min_bps` and then rises linearly to `max_bps` over the
+//last `ramp_bps` share of `duration`, rounded up because it is charged
+pub fn time_decay_fee_bps(
+    min_bps: u16,
+    max_bps: u16,
+    ramp_bps: u16,
+    duration: u64,
+    elapsed: u64,
+) -> Result<u64> {
+    require_gte!(max_bps, min_bps, MarketError::InvalidFeeCurve);
+
+    let ramp_len = mul_div_floor(duration, ramp_bps as u64, BPS_DENOMINATOR)?;
+    let ramp_start = checked_sub(duration, ramp_len)?;
+
+    if ramp_len == 0 || elapsed <= ramp_start {
+        return Ok(min_bps as u64);
+    }
+
+    let progressed = (elapsed - ramp_start).min(ramp_len);
+    let increase = mul_div_ceil((max_bps - min_bps) as u64, progressed, ramp_len)?;
+
+    checked_add(min_bps as u64, increase)
+}
+
+//rescales `value * 10^exponent` into units of `10^target_exponent`, rounding down,
+//the flag is set when the rounding dropped a non-zero remainder
+pub fn scale_to_exponent(value: u64, exponent: i32, target_exponent: i32) -> Result<(u64, bool)> {
+    let shift = (exponent as i64) - (target_exponent as i64);
+    let factor = u32::try_from(shift.unsigned_abs())
+        .ok()
+        .and_then(|shift| 10_u128.checked_pow(shift));
+
+    if shift >= 0 {
+        let scaled = factor
+            .and_then(|factor| (value as u128).checked_mul(factor))
+            .ok_or(MarketError::PriceAdjustmentOverflow)?;
+
+        let scaled = u64::try_from(scaled).map_err(|_| MarketError::PriceAdjustmentOverflow)?;
+        Ok((scaled, false))
+    } else {
+        //a divisor beyond u128 leaves nothing of the value
+        let Some(factor) = factor else {
+            return Ok((0, value != 0));
+        };
+
+        let value = value as u128;
+        Ok(((value / factor) as u64, value % factor != 0))
+    }
+}
+
+//middle value of `values`, the average of the two middle values rounded down when their number is even
+pub fn median(values: &[u64]) -> Option<u64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+
+    let upper = *sorted.get(sorted.len() / 2)?;
+    if sorted.len() % 2 == 1 {
+        return Some(upper);
+    }
+
+    let lower = sorted[sorted.len() / 2 - 1];
+    Some(lower + (upper - lower) / 2)
+}
+
+//whether `value` is within `tolerance_bps` of `reference`
+pub fn within_tolerance(value: u64, reference: u64, tolerance_bps: u64) -> bool {
+    let deviation = value.abs_diff(reference) as u128 * BPS_DENOMINATOR as u128;
+
+    deviation <= reference as u128 * tolerance_bps as u128
+}
+
+//LP tokens for a deposit worth `deposit`, priced at the `surplus` the `lp_supply` tokens already
+//minted can withdraw, there is no price once the LPs are underwater
+pub fn lp_tokens(deposit: u64, lp_supply: u64, surplus: u64) -> Result<u64> {
+    require_gt!(surplus, 0, MarketError::PoolsUnderwater);
+
+    mul_div_floor(deposit, lp_supply, surplus)
+}
+
+pub fn checked_add(a: u64, b: u64) -> Result<u64> {
+    a.checked_add(b).ok_or(error!(MarketError::MathOverflow))
+}
+
+pub fn checked_sub(a: u64, b: u64) -> Result<u64> {
+    a.checked_sub(b).ok_or(error!(MarketError::MathOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USDC: u64 = 1_000_000;
+
+    #[test]
+    fn mul_div_rounds_in_the_requested_direction() {
+        assert_eq!(mul_div_floor(10, 1, 3).unwrap(), 3);
+        assert_eq!(mul_div_ceil(10, 1, 3).unwrap(), 4);
+        assert_eq!(mul_div_floor(9, 1, 3).unwrap(), 3);
+        assert_eq!(mul_div_ceil(9, 1, 3).unwrap(), 3);
+        assert_eq!(mul_div_ceil(0, 7, 3).unwrap(), 0);
+    }
+
+    #[test]
+    fn mul_div_uses_wide_intermediates() {
+        assert_eq!(
+            mul_div_floor(u64::MAX, u64::MAX, u64::MAX).unwrap(),
+            u64::MAX
+        );
+        assert_eq!(mul_div_ceil(u64::MAX, 2, 2).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn mul_div_rejects_results_that_do_not_fit() {
+        assert!(mul_div_floor(u64::MAX, 2, 1).is_err());
+        assert!(mul_div_ceil(u64::MAX, u64::MAX, u64::MAX - 1).is_err());
+    }
+
+    #[test]
+    fn mul_div_rejects_zero_denominator() {
+        assert!(mul_div_floor(1, 1, 0).is_err());
+        assert!(mul_div_ceil(1, 1, 0).is_err());
+    }
+
+    #[test]
+    fn odds_of_empty_opposite_pool_are_even() {
+        assert_eq!(odds(0, 0).unwrap(), ODDS_FIXED_POINT_MULTIPLIER);
+        assert_eq!(odds(5 * USDC, 0).unwrap(), ODDS_FIXED_POINT_MULTIPLIER);
+    }
+
+    #[test]
+    fn odds_above_u64_product_range() {
+        //the old u64 product overflowed once a pool held ~18M USDC
+        let pool = 20_000_000 * USDC;
+        assert_eq!(odds(pool, pool).unwrap(), ODDS_FIXED_POINT_MULTIPLIER);
+        assert_eq!(
+            odds(2 * pool, pool).unwrap(),
+            2 * ODDS_FIXED_POINT_MULTIPLIER
+        );
+        assert_eq!(
+            odds(u64::MAX, u64::MAX).unwrap(),
+            ODDS_FIXED_POINT_MULTIPLIER
+        );
+    }
+
+    #[test]
+    fn odds_round_down() {
+        assert_eq!(odds(1, 3).unwrap(), 333_333);
+        assert_eq!(odds(2, 3).unwrap(), 666_666);
+    }
+
+    #[test]
+    fn odds_overflow_is_an_error() {
+        assert!(odds(u64::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn payout_rounds_down() {
+        assert_eq!(
+            payout(10 * USDC, ODDS_FIXED_POINT_MULTIPLIER).unwrap(),
+            10 * USDC
+        );
+        assert_eq!(payout(1, 333_333).unwrap(), 0);
+        assert_eq!(payout(3, 333_333).unwrap(), 0);
+        assert_eq!(payout(7, 1_500_000).unwrap(), 10);
+        assert_eq!(payout(0, u64::MAX).unwrap(), 0);
+    }
+
+    #[test]
+    fn payout_of_large_bets() {
+        assert_eq!(
+            payout(u64::MAX, ODDS_FIXED_POINT_MULTIPLIER).unwrap(),
+            u64::MAX
+        );
+        assert!(payout(u64::MAX, 2 * ODDS_FIXED_POINT_MULTIPLIER).is_err());
+    }
+
+    #[test]
+    fn fee_rounds_up() {
+        assert_eq!(fee(10_000, 30).unwrap(), 30);
+        assert_eq!(fee(1, 1).unwrap(), 1);
+        assert_eq!(fee(9_999, 1).unwrap(), 1);
+        assert_eq!(fee(10_001, 1).unwrap(), 2);
+        assert_eq!(fee(0, 30).unwrap(), 0);
+        assert_eq!(fee(USDC, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn fee_bounds() {
+        assert_eq!(fee(u64::MAX, BPS_DENOMINATOR).unwrap(), u64::MAX);
+        assert_eq!(fee(u64::MAX, 1).unwrap(), u64::MAX / 10_000 + 1);
+        assert!(fee(USDC, BPS_DENOMINATOR + 1).is_err());
+    }
+
+    #[test]
+    fn confidence_bps_rounds_up() {
+        assert_eq!(confidence_bps(1_000_000, 14_000_000_000).unwrap(), 1);
+        assert_eq!(confidence_bps(14_000_000, 14_000_000_000).unwrap(), 10);
+        assert_eq!(confidence_bps(0, 14_000_000_000).unwrap(), 0);
+        assert_eq!(confidence_bps(u64::MAX, 1).unwrap(), u64::MAX);
+        assert!(confidence_bps(1, 0).is_err());
+    }
+
+    #[test]
+    fn time_decay_fee_is_flat_before_the_ramp() {
+        assert_eq!(time_decay_fee_bps(10, 500, 2_000, 1_000, 0).unwrap(), 10);
+        assert_eq!(time_decay_fee_bps(10, 500, 2_000, 1_000, 800).unwrap(), 10);
+    }
+
+    #[test]
+    fn time_decay_fee_rises_linearly_over_the_ramp() {
+        assert_eq!(time_decay_fee_bps(10, 500, 2_000, 1_000, 900).unwrap(), 255);
+        assert_eq!(
+            time_decay_fee_bps(10, 500, 2_000, 1_000, 1_000).unwrap(),
+            500
+        );
+        assert_eq!(time_decay_fee_bps(0, 3, 2_000, 1_000, 801).unwrap(), 1);
+    }
+
+    #[test]
+    fn time_decay_fee_is_capped_after_expiry() {
+        assert_eq!(
+            time_decay_fee_bps(10, 500, 2_000, 1_000, 5_000).unwrap(),
+            500
+        );
+        assert_eq!(
+            time_decay_fee_bps(10, 500, 10_000, 1_000, u64::MAX).unwrap(),
+            500
+        );
+    }
+
+    #[test]
+    fn time_decay_fee_without_a_ramp() {
+        assert_eq!(time_decay_fee_bps(10, 500, 0, 1_000, 1_000).unwrap(), 10);
+        assert_eq!(time_decay_fee_bps(10, 500, 2_000, 0, 0).unwrap(), 10);
+    }
+
+    #[test]
+    fn time_decay_fee_rejects_inverted_curves() {
+        assert!(time_decay_fee_bps(500, 10, 2_000, 1_000, 0).is_err());
+    }
+
+    #[test]
+    fn scale_pyth_style_exponents_to_whole_units() {
+        //$140.12345678 with exponent -8 and $140.12345 with exponent -5
+        assert_eq!(scale_to_exponent(14_012_345_678, -8, 0).unwrap(), (140, true));
+        assert_eq!(scale_to_exponent(14_012_345, -5, 0).unwrap(), (140, true));
+        assert_eq!(scale_to_exponent(14_000_000_000, -8, 0).unwrap(), (140, false));
+        assert_eq!(scale_to_exponent(14_000_000, -5, 0).unwrap(), (140, false));
+    }
+
+    #[test]
+    fn scale_between_oracle_and_target_exponents() {
+        assert_eq!(scale_to_exponent(14_012_345, -5, -8).unwrap(), (14_012_345_000, false));
+        assert_eq!(scale_to_exponent(14_012_345_678, -8, -5).unwrap(), (14_012_345, true));
+        assert_eq!(scale_to_exponent(14_012_345_678, -8, -8).unwrap(), (14_012_345_678, false));
+        assert_eq!(scale_to_exponent(140, 0, -2).unwrap(), (14_000, false));
+        assert_eq!(scale_to_exponent(14_000, -2, 2).unwrap(), (1, true));
+    }
+
+    #[test]
+    fn scale_overflow_is_an_error() {
+        assert!(scale_to_exponent(u64::MAX, 0, -1).is_err());
+        assert!(scale_to_exponent(1, 0, -39).is_err());
+        assert!(scale_to_exponent(1, i32::MAX, i32::MIN).is_err());
+    }
+
+    #[test]
+    fn scale_far_below_the_target_rounds_to_zero() {
+        assert_eq!(scale_to_exponent(u64::MAX, -40, 0).unwrap(), (0, true));
+        assert_eq!(scale_to_exponent(0, i32::MIN, i32::MAX).unwrap(), (0, false));
+    }
+
+    #[test]
+    fn median_of_odd_and_even_counts() {
+        assert_eq!(median(&[]), None);
+        assert_eq!(median(&[7]), Some(7));
+        assert_eq!(median(&[9, 7]), Some(8));
+        assert_eq!(median(&[10, 1, 7, 3]), Some(5));
+        assert_eq!(median(&[u64::MAX, u64::MAX - 2]), Some(u64::MAX - 1));
+        assert_eq!(median(&[9, 1, 7]), Some(7));
+        assert_eq!(median(&[u64::MAX, 0, 5]), Some(5));
+    }
+
+    #[test]
+    fn tolerance_is_relative_to_the_reference() {
+        assert!(within_tolerance(14_000, 14_000, 0));
+        assert!(within_tolerance(14_014, 14_000, 10));
+        assert!(within_tolerance(13_986, 14_000, 10));
+        assert!(!within_tolerance(14_015, 14_000, 10));
+        assert!(!within_tolerance(1, 0, BPS_DENOMINATOR));
+        assert!(within_tolerance(u64::MAX, u64::MAX - 1, 1));
+    }
+
+    #[test]
+    fn lp_tokens_are_priced_at_the_surplus() {
+        //100 LP tokens backed by 300 of surplus after bets lost
+        assert_eq!(lp_tokens(30 * USDC, 100 * USDC, 300 * USDC).unwrap(), 10 * USDC);
+        assert_eq!(lp_tokens(USDC, USDC, 2 * USDC).unwrap(), USDC / 2);
+        assert_eq!(lp_tokens(1, 2, 3).unwrap(), 0);
+        assert!(lp_tokens(USDC, USDC, 0).is_err());
+    }
+
+    #[test]
+    fn checked_add_and_sub() {
+        assert_eq!(checked_add(1, 2).unwrap(), 3);
+        assert!(checked_add(u64::MAX, 1).is_err());
+        assert_eq!(checked_sub(3, 2).unwrap(), 1);
+        assert!(checked_sub(0, 1).is_err());
+    }
+}
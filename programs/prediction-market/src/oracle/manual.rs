@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::oracle::{check_price_age, OraclePrice, PriceOracle};
+use crate::states::ManualOracle;
+use crate::MarketError;
+
+//authority-signed ManualOracle accounts, the feed id is the address of the oracle account
+pub struct ManualOracleFeed {
+    feed: Pubkey,
+    oracle: ManualOracle,
+}
+
+impl ManualOracleFeed {
+    pub fn load(price_account: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(*price_account.owner, crate::ID, MarketError::InvalidOracleAccount);
+
+        let data = price_account.try_borrow_data()?;
+        let oracle = ManualOracle::try_deserialize(&mut &data[..])?;
+
+        Ok(Self {
+            feed: price_account.key(),
+            oracle,
+        })
+    }
+}
+
+impl PriceOracle for ManualOracleFeed {
+    fn read_price(&self, feed_id: &[u8; 32], clock: &Clock, max_age: u64) -> Result<OraclePrice> {
+        require!(self.feed.to_bytes() == *feed_id, MarketError::OracleFeedMismatch);
+        require_neq!(self.oracle.publish_time, 0, MarketError::InvalidOraclePrice);
+        check_price_age(self.oracle.publish_time, clock, max_age)?;
+
+        Ok(OraclePrice {
+            price: self.oracle.price,
+            exponent: self.oracle.exponent,
+            conf: self.oracle.conf,
+            publish_time: self.oracle.publish_time,
+        })
+    }
+}
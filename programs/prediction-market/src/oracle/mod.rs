@@ -0,0 +1,199 @@
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::VerificationLevel;
+
+use crate::math;
+use crate::states::{Market, OracleFeed, OracleReading, OracleSource};
+use crate::MarketError;
+
+pub mod manual;
+pub mod pyth;
+pub mod switchboard;
+
+pub use manual::*;
+pub use pyth::*;
+pub use switchboard::*;
+
+//price reading normalized across oracle providers, the value is `price * 10^exponent`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OraclePrice {
+    pub price: i64,
+    pub exponent: i32,
+    pub conf: u64,
+    pub publish_time: i64,
+}
+
+pub trait PriceOracle {
+    //reads the price published for `feed_id`, rejecting readings older than `max_age` seconds
+    fn read_price(&self, feed_id: &[u8; 32], clock: &Clock, max_age: u64) -> Result<OraclePrice>;
+}
+
+//loads the adapter of `source` from `price_account` and reads `feed_id` from it,
+//`pyth_verification` is the weakest pyth update accepted
+pub fn read_oracle_price(
+    source: &OracleSource,
+    pyth_verification: VerificationLevel,
+    price_account: &AccountInfo,
+    feed_id: &[u8; 32],
+    clock: &Clock,
+    max_age: u64,
+) -> Result<OraclePrice> {
+    match source {
+        OracleSource::PythPull => {
+            PythPullOracle::load(price_account, pyth_verification)?.read_price(feed_id, clock, max_age)
+        }
+        OracleSource::SwitchboardOnDemand => {
+            SwitchboardOnDemandOracle::load(price_account)?.read_price(feed_id, clock, max_age)
+        }
+        OracleSource::Manual => {
+            ManualOracleFeed::load(price_account)?.read_price(feed_id, clock, max_age)
+        }
+    }
+}
+
+pub fn check_price_age(publish_time: i64, clock: &Clock, max_age: u64) -> Result<()> {
+    let max_age = i64::try_from(max_age).map_err(|_| MarketError::MathOverflow)?;
+
+    require_gte!(
+        publish_time.saturating_add(max_age),
+        clock.unix_timestamp,
+        MarketError::StalePrice
+    );
+
+    Ok(())
+}
+
+//reads `feed` and checks it is usable for settlement. Only prices published within the
+//max_price_age_secs of the feed after expiry are accepted, so the settler can not pick a price
+//from before expiry or from later in the settlement window. Non-positive prices and readings
+//with a confidence interval wider than the max_conf_bps of the feed are rejected
+pub fn read_settlement_price(
+    market: &Market,
+    feed: &OracleFeed,
+    price_account: &AccountInfo,
+    clock: &Clock,
+) -> Result<OraclePrice> {
+    //the adapters reject prices older than the given age, which here reaches back to expiry
+    let expiry = market.expiry_timestamp(clock)?;
+    let since_expiry = u64::try_from(clock.unix_timestamp.saturating_sub(expiry)).unwrap_or(0);
+    let price = read_oracle_price(
+        &feed.source,
+        market.pyth_verification_level(),
+        price_account,
+        &feed.feed_id,
+        clock,
+        since_expiry,
+    )?;
+
+    let max_age = i64::try_from(feed.max_price_age_secs).map_err(|_| MarketError::MathOverflow)?;
+    require!(
+        price.publish_time >= expiry && price.publish_time <= expiry.saturating_add(max_age),
+        MarketError::PriceOutsideSettlementWindow
+    );
+
+    require_gt!(price.price, 0, MarketError::NonPositivePrice);
+
+    let conf_bps = math::confidence_bps(price.conf, price.price as u64)?;
+    require_gte!(feed.max_conf_bps as u64, conf_bps, MarketError::PriceConfidenceTooWide);
+
+    Ok(price)
+}
+
+//reads the market feeds and stores the final price in the unit of the target price. A single
+//feed must give a valid reading so settlement can be retried otherwise. With extra feeds, the
+//price accounts of the extra feeds follow in `extra_price_accounts` in market order, invalid
+//readings are skipped and the median is used once enough readings agree with it. An account
+//that is not the registered feed aborts settlement
+pub fn settle_final_price<'info>(
+    market: &mut Market,
+    price_account: &AccountInfo<'info>,
+    extra_price_accounts: &[AccountInfo<'info>],
+    clock: &Clock,
+) -> Result<()> {
+    require!(!market.resolution_pending, MarketError::ResolutionPending);
+
+    let primary = OracleFeed {
+        source: market.oracle_source.clone(),
+        feed_id: market.feed_id,
+        max_price_age_secs: market.max_price_age_secs,
+        max_conf_bps: market.max_conf_bps,
+    };
+
+    if market.extra_feeds.is_empty() {
+        let price = read_settlement_price(market, &primary, price_account, clock)?;
+        return store_final_price(market, vec![OracleReading::new(primary, &price)]);
+    }
+
+    require_gte!(extra_price_accounts.len(), market.extra_feeds.len(), MarketError::IncompleteOracleAccounts);
+
+    let feeds = std::iter::once(primary).chain(market.extra_feeds.iter().cloned());
+    let accounts = std::iter::once(price_account).chain(extra_price_accounts.iter());
+
+    let mut readings = Vec::with_capacity(market.extra_feeds.len() + 1);
+    for (feed, account) in feeds.zip(accounts) {
+        match read_settlement_price(market, &feed, account, clock) {
+            Ok(price) => readings.push(OracleReading::new(feed, &price)),
+            Err(err) if is_skippable_reading(&err) => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    store_final_price(market, readings)
+}
+
+//a reading of the registered feed that is unusable, any of them can come from an honest oracle.
+//Accounts that are not the registered feed, including ones that do not deserialize, are not
+//skipped, a settler could otherwise drop a feed by passing some other account in its place
+fn is_skippable_reading(err: &Error) -> bool {
+    let mismatch = [MarketError::OracleFeedMismatch, MarketError::InvalidOracleAccount]
+        .into_iter()
+        .any(|code| *err == code.into());
+
+    match err {
+        Error::AnchorError(anchor_err) => !mismatch && anchor_err.error_code_number >= ERROR_CODE_OFFSET,
+        Error::ProgramError(_) => false,
+    }
+}
+
+//settles on the median of `readings`, which must hold at least min_agreeing_readings
+//readings within agreement_tolerance_bps of it
+fn store_final_price(market: &mut Market, readings: Vec<OracleReading>) -> Result<()> {
+    //readings are compared in the finest exponent among them so no precision is lost
+    let exponent = readings
+        .iter()
+        .map(|reading| reading.exponent)
+        .min()
+        .ok_or(MarketError::InsufficientOracleAgreement)?;
+
+    let values = readings
+        .iter()
+        .map(|reading| math::scale_to_exponent(reading.price as u64, reading.exponent, exponent).map(|(value, _)| value))
+        .collect::<Result<Vec<_>>>()?;
+
+    let median = math::median(&values).ok_or(MarketError::InsufficientOracleAgreement)?;
+    let agreeing = values
+        .iter()
+        .filter(|value| math::within_tolerance(**value, median, market.agreement_tolerance_bps as u64))
+        .count();
+    require_gte!(agreeing, market.min_agreeing_readings as usize, MarketError::InsufficientOracleAgreement);
+
+    //the confidence is taken from the reading closest to the median, which can fall between two of them
+    let median_reading = values
+        .iter()
+        .zip(readings.iter())
+        .min_by_key(|(value, _)| value.abs_diff(median))
+        .map(|(_, reading)| reading)
+        .ok_or(MarketError::InsufficientOracleAgreement)?;
+
+    //prices are compared in the unit of the target price, see Market::winning_direction. The
+    //confidence is kept as published, rescaling it to a coarse target unit would round it to 0
+    let (final_price, truncated) = math::scale_to_exponent(median, exponent, market.target_exponent)?;
+
+    market.final_price = Some(final_price);
+    market.final_price_truncated = truncated;
+    market.final_conf = Some(median_reading.conf);
+    market.final_conf_exponent = median_reading.exponent;
+    market.settlement_readings = readings;
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::{PriceUpdateV2, VerificationLevel};
+
+use crate::oracle::{check_price_age, OraclePrice, PriceOracle};
+use crate::MarketError;
+
+//pull updates posted through the pyth solana receiver, feed ids are pyth price feed ids
+pub struct PythPullOracle {
+    price_update: PriceUpdateV2,
+    required_verification: VerificationLevel,
+}
+
+impl PythPullOracle {
+    pub fn load(price_account: &AccountInfo, required_verification: VerificationLevel) -> Result<Self> {
+        require_keys_eq!(
+            *price_account.owner,
+            pyth_solana_receiver_sdk::ID,
+            MarketError::InvalidOracleAccount
+        );
+
+        let data = price_account.try_borrow_data()?;
+        let price_update = PriceUpdateV2::try_deserialize(&mut &data[..])?;
+
+        Ok(Self {
+            price_update,
+            required_verification,
+        })
+    }
+}
+
+impl PriceOracle for PythPullOracle {
+    fn read_price(&self, feed_id: &[u8; 32], clock: &Clock, max_age: u64) -> Result<OraclePrice> {
+        require!(
+            self.price_update.verification_level.gte(self.required_verification),
+            MarketError::InsufficientPriceVerification
+        );
+
+        let price = self
+            .price_update
+            .get_price_unchecked(feed_id)
+            .map_err(|_| MarketError::OracleFeedMismatch)?;
+        check_price_age(price.publish_time, clock, max_age)?;
+
+        Ok(OraclePrice {
+            price: price.price,
+            exponent: price.exponent,
+            conf: price.conf,
+            publish_time: price.publish_time,
+        })
+    }
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+use crate::oracle::{check_price_age, OraclePrice, PriceOracle};
+use crate::MarketError;
+
+pub const SWITCHBOARD_ON_DEMAND_MAINNET_PID: Pubkey =
+    pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+pub const SWITCHBOARD_ON_DEMAND_DEVNET_PID: Pubkey =
+    pubkey!("Aio4gaXjXzJNVLtzwtNVmSqGKpANtXhybbkhtAC94ji2");
+
+//layout of the on-demand PullFeedAccountData account, read directly because the
+//switchboard-on-demand crate pins a borsh release anchor 0.30 can not build with
+const PULL_FEED_DISCRIMINATOR: [u8; 8] = [196, 27, 108, 196, 10, 215, 219, 40];
+const LAST_UPDATE_TIMESTAMP_OFFSET: usize = 2216;
+const RESULT_VALUE_OFFSET: usize = 2264;
+const RESULT_STD_DEV_OFFSET: usize = 2280;
+const RESULT_SLOT_OFFSET: usize = 2368;
+const PULL_FEED_MIN_LEN: usize = 2392;
+
+//switchboard results carry 18 decimals, they are scaled down to fit an i64
+const SWITCHBOARD_PRECISION: u32 = 18;
+const SWITCHBOARD_NORMALIZED_EXPONENT: i32 = -8;
+
+//switchboard on-demand pull feeds, the feed id is the address of the pull feed account
+pub struct SwitchboardOnDemandOracle {
+    feed: Pubkey,
+    value: i128,
+    std_dev: i128,
+    result_slot: u64,
+    last_update_timestamp: i64,
+}
+
+impl SwitchboardOnDemandOracle {
+    pub fn load(price_account: &AccountInfo) -> Result<Self> {
+        require!(
+            *price_account.owner == SWITCHBOARD_ON_DEMAND_MAINNET_PID
+                || *price_account.owner == SWITCHBOARD_ON_DEMAND_DEVNET_PID,
+            MarketError::InvalidOracleAccount
+        );
+
+        let data = price_account.try_borrow_data()?;
+        require_gte!(data.len(), PULL_FEED_MIN_LEN, MarketError::InvalidOracleAccount);
+        require!(data[..8] == PULL_FEED_DISCRIMINATOR, MarketError::InvalidOracleAccount);
+
+        Ok(Self {
+            feed: price_account.key(),
+            value: read_i128(&data, RESULT_VALUE_OFFSET),
+            std_dev: read_i128(&data, RESULT_STD_DEV_OFFSET),
+            result_slot: read_u64(&data, RESULT_SLOT_OFFSET),
+            last_update_timestamp: read_u64(&data, LAST_UPDATE_TIMESTAMP_OFFSET) as i64,
+        })
+    }
+}
+
+impl PriceOracle for SwitchboardOnDemandOracle {
+    fn read_price(&self, feed_id: &[u8; 32], clock: &Clock, max_age: u64) -> Result<OraclePrice> {
+        require!(self.feed.to_bytes() == *feed_id, MarketError::OracleFeedMismatch);
+        require_neq!(self.result_slot, 0, MarketError::InvalidOraclePrice);
+        check_price_age(self.last_update_timestamp, clock, max_age)?;
+
+        let scale = 10_i128.pow(SWITCHBOARD_PRECISION - SWITCHBOARD_NORMALIZED_EXPONENT.unsigned_abs());
+
+        Ok(OraclePrice {
+            price: i64::try_from(self.value / scale).map_err(|_| MarketError::PriceAdjustmentOverflow)?,
+            exponent: SWITCHBOARD_NORMALIZED_EXPONENT,
+            conf: u64::try_from(self.std_dev / scale).map_err(|_| MarketError::PriceAdjustmentOverflow)?,
+            publish_time: self.last_update_timestamp,
+        })
+    }
+}
+
+fn read_i128(data: &[u8], offset: usize) -> i128 {
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&data[offset..offset + 16]);
+    i128::from_le_bytes(bytes)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
@@ -1,5 +1,11 @@
 use anchor_lang::prelude::*;
 use num_derive::*;
+use pyth_solana_receiver_sdk::price_update::VerificationLevel;
+
+use crate::constants::*;
+use crate::math;
+use crate::oracle::OraclePrice;
+use crate::MarketError;
 
 #[account]
 #[derive(InitSpace)]
@@ -13,8 +19,253 @@ pub struct Market {
     pub higher_pool_bump: u8,
     pub final_price: Option<u64>,
     pub lower_pool_bump: u8,
-    pub feed_id: [u8; 66], // from https://pyth.network/developers/price-feed-ids#solana-stables
+    pub feed_id: [u8; 32], // from https://pyth.network/developers/price-feed-ids#solana-stables
     pub initialization: MarketInitialization,
+    pub lp_mint_bump: u8,
+    pub higher_liability: u64, // payouts still owed to unclaimed higher bets
+    pub lower_liability: u64,  // payouts still owed to unclaimed lower bets
+    pub creator_fee_bps: u16,
+    pub creator_fee_vault_bump: u8,
+    pub fee_curve: Option<FeeCurve>,
+    pub oracle_source: OracleSource,
+    pub max_conf_bps: u16, // widest accepted oracle confidence interval relative to the price
+    pub final_conf: Option<u64>, // oracle confidence of the median reading at settlement, in units of 10^final_conf_exponent
+    pub voided: bool, // no acceptable price within the settlement window, bets are refunded
+    pub higher_stake: u64, // stakes of unclaimed higher bets, refunded if the market is voided
+    pub lower_stake: u64,  // stakes of unclaimed lower bets, refunded if the market is voided
+    pub target_exponent: i32, // target_price and final_price are in units of 10^target_exponent
+    pub final_price_truncated: bool, // final_price was rounded down from a finer oracle price
+    pub max_price_age_secs: u64, // oldest oracle price accepted at settlement
+    pub settlement_window: u64,  // slots after expiry during which the market can be settled
+    pub min_pyth_signatures: Option<u8>, // accept partially verified pyth updates, fully verified only when None
+    pub feed_seed: [u8; 32], // feed part of the market seeds, depends on feed_version
+    pub feed_version: FeedVersion,
+    pub optimistic_resolution: Option<OptimisticResolution>, // bonded proposals allowed when set
+    pub resolution_pending: bool, // a proposal is open, oracle settlement and voiding wait for it
+    pub kind: MarketKind,
+    pub resolver: Option<Pubkey>, // decides the outcome of event markets
+    pub event_outcome: Option<bool>, // true when the event happened, higher bets are yes bets
+    #[max_len(2)]
+    pub extra_feeds: Vec<OracleFeed>, // settle on the median of the market feed and these
+    pub min_agreeing_readings: u8,
+    pub agreement_tolerance_bps: u16, // how far from the median a reading may be to agree with it
+    #[max_len(3)]
+    pub settlement_readings: Vec<OracleReading>, // valid readings the final price was taken from
+    pub seed_amount: u64, // collateral the creator seeded into each pool, less any transfer fee
+    pub min_bet: u64, // bet limits in base units, set at initialize_pools
+    pub max_bet: u64,
+    pub bet_limits: Option<BetLimits>, // creator limits in token units, applied once the mint is known
+    pub pause: PauseFlags, // creation stops initialize_pools of this market
+    pub counted_by_registry: bool, // holds a slot of the open market quota of the creator
+    pub creator_bond: u64, // received into the bond vault when the market was created
+    pub bond_vault_bump: u8,
+    pub bond_vault_open: bool, // until the bond is returned or slashed
+    pub start_timestamp: i64, // unix time of start_time, oracle prices are dated in unix time
+    pub voided_by_timeout: bool, // voided for want of a price or outcome, its bond can be slashed
+    pub final_conf_exponent: i32, // exponent the median reading was published in
+    pub claiming_paused: PausedSlots, // time settlement was stopped by the claiming flag of the market
+    pub protocol_paused_at_start: u64, // Config::claiming_paused at creation, only later protocol pauses count
+}
+
+impl Market {
+    //validates the creator settings shared by price and event markets and stores them
+    pub fn apply_params(&mut self, params: MarketParams, config: &Config) -> Result<()> {
+        let settlement_window = params.settlement_window.unwrap_or(config.default_settlement_window);
+
+        require!((MIN_TARGET_EXPONENT..=MAX_TARGET_EXPONENT).contains(&params.target_exponent), MarketError::InvalidTargetExponent);
+        require!(settlement_window > 0 && settlement_window <= config.max_settlement_window, MarketError::InvalidSettlementWindow);
+        require_neq!(params.min_pyth_signatures.unwrap_or(1), 0, MarketError::InvalidMinSignatures);
+        if let Some(optimistic) = &params.optimistic_resolution {
+            require!(
+                optimistic.bond > 0 && optimistic.challenge_window > 0 && optimistic.challenge_window <= config.max_settlement_window,
+                MarketError::InvalidOptimisticResolution
+            );
+        }
+        require_gte!(config.max_creator_fee_bps, params.creator_fee_bps, MarketError::CreatorFeeTooHigh);
+        require_gte!(MAX_EXTRA_ORACLE_FEEDS, params.extra_feed_ids.len(), MarketError::InvalidOracleAgreement);
+        require!(
+            params.min_agreeing_readings > 0 && params.min_agreeing_readings as usize <= params.extra_feed_ids.len() + 1,
+            MarketError::InvalidOracleAgreement
+        );
+        require_gte!(BPS_DENOMINATOR, params.agreement_tolerance_bps as u64, MarketError::InvalidOracleAgreement);
+        if let Some(limits) = &params.bet_limits {
+            require!(limits.min_bet > 0 && limits.min_bet <= limits.max_bet, MarketError::InvalidBetLimits);
+        }
+
+        if let Some(curve) = &params.fee_curve {
+            require_gte!(curve.fee_max_bps, curve.fee_min_bps, MarketError::InvalidFeeCurve);
+            require_gte!(BPS_DENOMINATOR, curve.ramp_bps as u64, MarketError::InvalidFeeCurve);
+
+            //the steepest point of the curve must still leave a stake behind
+            let max_total_fee_bps = config.protocol_fee_bps as u64
+                + config.lp_fee_bps as u64
+                + params.creator_fee_bps as u64
+                + curve.fee_max_bps as u64;
+            require_gt!(BPS_DENOMINATOR, max_total_fee_bps, MarketError::InvalidFeeBps);
+        }
+
+        self.creator_fee_bps = params.creator_fee_bps;
+        self.fee_curve = params.fee_curve;
+        self.target_exponent = params.target_exponent;
+        self.settlement_window = settlement_window;
+        self.min_pyth_signatures = params.min_pyth_signatures;
+        self.optimistic_resolution = params.optimistic_resolution;
+        self.min_agreeing_readings = params.min_agreeing_readings;
+        self.agreement_tolerance_bps = params.agreement_tolerance_bps;
+        self.bet_limits = params.bet_limits;
+
+        Ok(())
+    }
+
+    //the outcome is known, from an oracle price, an optimistic resolution or the event resolver
+    pub fn is_settled(&self) -> bool {
+        self.final_price.is_some() || self.event_outcome.is_some()
+    }
+
+    //slot at which betting stops and the market can be settled
+    pub fn expiry(&self) -> Result<u64> {
+        math::checked_add(self.start_time, self.market_duration)
+    }
+
+    //unix time of the expiry slot, interpolated from the slots and seconds elapsed since the start
+    pub fn expiry_timestamp(&self, clock: &Clock) -> Result<i64> {
+        let elapsed_slots = clock.slot.saturating_sub(self.start_time);
+        let elapsed_secs = u64::try_from(clock.unix_timestamp.saturating_sub(self.start_timestamp)).unwrap_or(0);
+        let expiry_offset = math::mul_div_floor(elapsed_secs, self.market_duration, elapsed_slots)?;
+
+        Ok(self.start_timestamp.saturating_add(i64::try_from(expiry_offset).map_err(|_| MarketError::MathOverflow)?))
+    }
+
+    //what LPs could withdraw if the side with the larger payouts won, both seeds are set aside
+    pub fn lp_surplus(&self, higher_pool_amount: u64, lower_pool_amount: u64) -> Result<u64> {
+        let reserved = math::checked_add(self.seed_amount, self.seed_amount)?;
+        let reserved = math::checked_add(reserved, self.higher_liability.max(self.lower_liability))?;
+
+        Ok(math::checked_add(higher_pool_amount, lower_pool_amount)?.saturating_sub(reserved))
+    }
+
+    //slot after which the bond of a market that is still unresolved can be slashed
+    pub fn bond_slash_deadline(&self, config: &Config, slot: u64) -> u64 {
+        self.settlement_deadline(config, slot).saturating_add(config.bond_slash_delay)
+    }
+
+    //last slot at which a final price is accepted, the market can be voided after it. Pushed
+    //back by every slot claiming was paused, so a pause never runs the settlement window out
+    pub fn settlement_deadline(&self, config: &Config, slot: u64) -> u64 {
+        self.start_time
+            .saturating_add(self.market_duration)
+            .saturating_add(self.settlement_window)
+            .saturating_add(self.paused_slots(config, slot))
+    }
+
+    //slots up to `slot` the market could not be settled for a protocol or market pause, pauses
+    //that overlap count twice and pauses before expiry count too, both only delay the deadline
+    pub fn paused_slots(&self, config: &Config, slot: u64) -> u64 {
+        let protocol = config.claiming_paused.at(slot).saturating_sub(self.protocol_paused_at_start);
+        self.claiming_paused.at(slot).saturating_add(protocol)
+    }
+
+    //weakest pyth update verification the market settles on
+    pub fn pyth_verification_level(&self) -> VerificationLevel {
+        match self.min_pyth_signatures {
+            Some(num_signatures) => VerificationLevel::Partial { num_signatures },
+            None => VerificationLevel::Full,
+        }
+    }
+
+    //direction that wins once the final price is known, None when unsettled or on a tie
+    pub fn winning_direction(&self) -> Option<Direction> {
+        if self.kind == MarketKind::Event {
+            return self.event_outcome.map(|happened| if happened { Direction::Higher } else { Direction::Lower });
+        }
+
+        let final_price = self.final_price?;
+
+        //a rounded down price equal to the target was strictly above it
+        if final_price > self.target_price || (final_price == self.target_price && self.final_price_truncated) {
+            Some(Direction::Higher)
+        } else if final_price < self.target_price {
+            Some(Direction::Lower)
+        } else {
+            None
+        }
+    }
+}
+
+//late-betting fee that rises from fee_min_bps to fee_max_bps over the
+//last ramp_bps share of the market duration
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq, Eq)]
+pub struct FeeCurve {
+    pub fee_min_bps: u16,
+    pub fee_max_bps: u16,
+    pub ramp_bps: u16,
+}
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, InitSpace, ToPrimitive, FromPrimitive, PartialEq, Eq,
+)]
+pub enum MarketKind {
+    Price, // settles on the feed price against target_price
+    Event, // yes/no outcome set by the resolver, feed_id and target_price are unused
+}
+
+//how the feed seed of a market is derived, legacy markets keep the address they were created at
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, InitSpace, ToPrimitive, FromPrimitive, PartialEq, Eq,
+)]
+pub enum FeedVersion {
+    LegacyHex, // sha256 of the 66-byte 0x-prefixed hex feed id
+    Bytes,     // the 32 feed id bytes
+    Event,     // the event id of an event market
+}
+
+//oracle program the final price is read from, see crate::oracle for the adapters
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, InitSpace, ToPrimitive, FromPrimitive, PartialEq, Eq,
+)]
+pub enum OracleSource {
+    PythPull,
+    SwitchboardOnDemand,
+    Manual,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq, Eq)]
+pub struct OracleFeed {
+    pub source: OracleSource,
+    pub feed_id: [u8; 32],
+    pub max_price_age_secs: u64, // limits of the feed registry, each feed is read against its own
+    pub max_conf_bps: u16,
+}
+
+//oracle price read at settlement, kept on the market for auditing
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq, Eq)]
+pub struct OracleReading {
+    pub source: OracleSource,
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub exponent: i32,
+    pub conf: u64,
+    pub publish_time: i64,
+}
+
+impl OracleReading {
+    pub fn new(feed: OracleFeed, price: &OraclePrice) -> Self {
+        Self {
+            source: feed.source,
+            feed_id: feed.feed_id,
+            price: price.price,
+            exponent: price.exponent,
+            conf: price.conf,
+            publish_time: price.publish_time,
+        }
+    }
+}
+
+//bond in market mint units posted by proposers and disputers, challenge_window is in slots
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq, Eq)]
+pub struct OptimisticResolution {
+    pub bond: u64,
+    pub challenge_window: u64,
 }
 
 #[derive(
@@ -32,6 +283,36 @@ pub enum MarketInitialization {
     InitializedMarket,
     InitializedPools,
 }
+//layout of markets created before feed ids were stored as bytes, only read by migrate_legacy_market
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct LegacyMarket {
+    pub creator: Pubkey,
+    pub target_price: u64,
+    pub start_time: u64,
+    pub market_duration: u64,
+    pub bump: u8,
+    pub mint: Pubkey,
+    pub higher_pool_bump: u8,
+    pub final_price: Option<u64>,
+    pub lower_pool_bump: u8,
+    pub feed_id: [u8; 66],
+    pub initialization: MarketInitialization,
+}
+
+//layout of bets placed before fees were charged, only read by migrate_legacy_bet
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct LegacyBet {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub amount: u64,
+    pub direction: Direction,
+    pub claimed: bool,
+    pub bump: u8,
+    pub initialized: bool,
+    pub odds: u64,
+    pub is_won: bool,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Bet {
@@ -44,4 +325,385 @@ pub struct Bet {
     pub initialized: bool,
     pub odds: u64,
     pub is_won: bool,
+    pub stake: u64, // amount left in the pool after fees, payouts are computed on it
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub lp_fee: u64,
+    pub dynamic_fee_bps: u16, // fee curve rate charged when the bet was placed
+    pub dynamic_fee: u64,
+    pub referrer: Option<Pubkey>,
+    pub referral_fee: u64, // part of the protocol fee credited to the referrer
+}
+
+//optimistic proposal of a market final price, one per market
+#[account]
+#[derive(InitSpace)]
+pub struct Resolution {
+    pub market: Pubkey,
+    pub proposer: Pubkey,
+    pub proposed_price: u64, // in units of the market target price
+    pub proposed_at: u64,
+    pub bond: u64,
+    pub disputer: Option<Pubkey>,
+    pub status: ResolutionStatus,
+    pub resolved_price: Option<u64>,
+    pub bump: u8,
+    pub vault_bump: u8,
+    pub paused_slots_at_proposal: u64, // Market::paused_slots when proposed, later pauses extend the arbitration window
+}
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, InitSpace, ToPrimitive, FromPrimitive, PartialEq, Eq,
+)]
+pub enum ResolutionStatus {
+    Proposed,
+    Disputed,
+    Resolved,
+}
+
+//per referrer and collateral mint, rewards are held in the referral vault of the mint
+#[account]
+#[derive(InitSpace)]
+pub struct ReferrerStats {
+    pub referrer: Pubkey,
+    pub mint: Pubkey,
+    pub referred_bets: u64,
+    pub referred_volume: u64,
+    pub total_rewards: u64,
+    pub unclaimed_rewards: u64,
+    pub bump: u8,
+}
+
+//price feed published by a trusted authority, markets reference it by its address
+#[account]
+#[derive(InitSpace)]
+pub struct ManualOracle {
+    pub authority: Pubkey,
+    pub feed_id: [u8; 32], // label chosen by the authority, part of the seeds
+    pub price: i64,
+    pub exponent: i32,
+    pub conf: u64,
+    pub publish_time: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub protocol_fee_bps: u16,
+    pub lp_fee_bps: u16,
+    pub max_creator_fee_bps: u16,
+    pub bump: u8,
+    pub referral_share_bps: u16, // share of the protocol fee paid to the referrer of a bet
+    pub max_price_age_secs: u64, // loosest staleness limit a market may use
+    pub max_settlement_window: u64, // longest settlement window in slots a market may use
+    pub arbiter: Pubkey, // decides disputed optimistic resolutions
+    #[max_len(8)]
+    pub trusted_mints: Vec<Pubkey>, // may back markets despite extensions rejected by utils::check_mint_extensions
+    pub min_market_duration: u64, // shortest betting period in slots
+    pub market_lock_period: u64, // slots after expiry before a creator can finalize, none when 0
+    pub default_settlement_window: u64, // settlement window of markets that do not choose one
+    pub pause: PauseFlags, // changed with set_protocol_pause
+    pub pending_admin: Option<Pubkey>, // proposed by the admin, becomes admin once it accepts
+    pub guardian: Pubkey, // can pause the protocol and markets, only the admin can unpause
+    pub permissioned_creation: bool, // only creators with a CreatorRegistry can create markets
+    pub bond_slash_delay: u64, // slots after the settlement deadline the creator has to resolve a market
+    pub pool_seed_amount: u64, // base units the creator seeds into each pool
+    pub arbitration_window: u64, // slots after the challenge window the arbiter has to decide a dispute
+    pub claiming_paused: PausedSlots, // time settlement was stopped by the claiming flag of the protocol
+}
+
+impl Config {
+    //validates and stores the admin-tunable parameters
+    pub fn apply(&mut self, params: &ConfigParams) -> Result<()> {
+        let total_fee_bps = params.protocol_fee_bps as u64
+            + params.lp_fee_bps as u64
+            + params.max_creator_fee_bps as u64;
+        require_gt!(BPS_DENOMINATOR, total_fee_bps, MarketError::InvalidFeeBps);
+        require_gte!(BPS_DENOMINATOR, params.referral_share_bps as u64, MarketError::InvalidFeeBps);
+        require_gt!(params.max_price_age_secs, 0, MarketError::InvalidOracleLimits);
+        require_gt!(params.max_settlement_window, 0, MarketError::InvalidOracleLimits);
+        require_gte!(MAX_TRUSTED_MINTS, params.trusted_mints.len(), MarketError::TooManyTrustedMints);
+        require_gt!(params.min_market_duration, 0, MarketError::ShortMarketDuration);
+        require_gt!(params.pool_seed_amount, 0, MarketError::InvalidPoolSeedAmount);
+        require_gt!(params.arbitration_window, 0, MarketError::InvalidArbitrationWindow);
+        require!(
+            params.default_settlement_window > 0 && params.default_settlement_window <= params.max_settlement_window,
+            MarketError::InvalidSettlementWindow
+        );
+
+        self.protocol_fee_bps = params.protocol_fee_bps;
+        self.lp_fee_bps = params.lp_fee_bps;
+        self.max_creator_fee_bps = params.max_creator_fee_bps;
+        self.referral_share_bps = params.referral_share_bps;
+        self.max_price_age_secs = params.max_price_age_secs;
+        self.max_settlement_window = params.max_settlement_window;
+        self.arbiter = params.arbiter;
+        self.trusted_mints = params.trusted_mints.clone();
+        self.min_market_duration = params.min_market_duration;
+        self.market_lock_period = params.market_lock_period;
+        self.default_settlement_window = params.default_settlement_window;
+        self.guardian = params.guardian;
+        self.permissioned_creation = params.permissioned_creation;
+        self.bond_slash_delay = params.bond_slash_delay;
+        self.pool_seed_amount = params.pool_seed_amount;
+        self.arbitration_window = params.arbitration_window;
+
+        Ok(())
+    }
+
+    //the admin can change any flag, the guardian can only set more of them
+    pub fn check_pause_authority(&self, authority: &Pubkey, current: &PauseFlags, new: &PauseFlags) -> Result<()> {
+        if *authority == self.admin {
+            return Ok(());
+        }
+        require_keys_eq!(*authority, self.guardian, MarketError::UnauthorizedUser);
+        require!(new.includes(current), MarketError::UnauthorizedUser);
+
+        Ok(())
+    }
+}
+
+//admin-registered feed a market can be listed on, its oracle settings are copied onto the market
+#[account]
+#[derive(InitSpace)]
+pub struct FeedRegistry {
+    pub feed_id: [u8; 32],
+    #[max_len(16)]
+    pub symbol: String,
+    pub oracle_source: OracleSource,
+    pub max_price_age_secs: u64,
+    pub max_conf_bps: u16,
+    pub enabled: bool,
+    pub bump: u8,
+}
+
+impl FeedRegistry {
+    //validates the feed settings against the protocol limits and stores them
+    pub fn apply(&mut self, params: &FeedParams, config: &Config) -> Result<()> {
+        require!(
+            !params.symbol.is_empty() && params.symbol.len() <= MAX_FEED_SYMBOL_LEN,
+            MarketError::InvalidFeedSymbol
+        );
+        require!(
+            params.max_conf_bps > 0 && params.max_conf_bps as u64 <= BPS_DENOMINATOR,
+            MarketError::InvalidConfidenceLimit
+        );
+        require!(
+            params.max_price_age_secs > 0 && params.max_price_age_secs <= config.max_price_age_secs,
+            MarketError::InvalidMaxPriceAge
+        );
+
+        self.symbol = params.symbol.clone();
+        self.oracle_source = params.oracle_source.clone();
+        self.max_price_age_secs = params.max_price_age_secs;
+        self.max_conf_bps = params.max_conf_bps;
+        self.enabled = params.enabled;
+
+        Ok(())
+    }
+}
+
+//bet size bounds in units of 10^-decimals tokens, converted to base units of the mint by initialize_pools
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq, Eq)]
+pub struct BetLimits {
+    pub min_bet: u64,
+    pub max_bet: u64,
+    pub decimals: u8,
+}
+
+impl BetLimits {
+    //converts the limits into base units of a mint with `mint_decimals`, limits finer than
+    //the mint can represent are rejected
+    pub fn to_base_units(&self, mint_decimals: u8) -> Result<(u64, u64)> {
+        let scale = |amount: u64| -> Result<u64> {
+            let (scaled, truncated) = math::scale_to_exponent(amount, -(self.decimals as i32), -(mint_decimals as i32))
+                .map_err(|_| error!(MarketError::InvalidBetLimits))?;
+            require!(!truncated, MarketError::InvalidBetLimits);
+
+            Ok(scaled)
+        };
+
+        Ok((scale(self.min_bet)?, scale(self.max_bet)?))
+    }
+}
+
+//admin-registered market creator, always used when it exists and required in permissioned mode
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorRegistry {
+    pub creator: Pubkey,
+    pub max_open_markets: u16,
+    pub open_markets: u16, // created and not yet finalized or cancelled
+    #[max_len(8)]
+    pub allowed_feeds: Vec<[u8; 32]>, // any feed when empty
+    pub bump: u8,
+    pub rent_payer: Pubkey, // refunded when the creator is removed
+}
+
+impl CreatorRegistry {
+    //validates and stores the admin-managed limits of the creator
+    pub fn apply(&mut self, params: &CreatorParams) -> Result<()> {
+        require_gte!(MAX_CREATOR_FEEDS, params.allowed_feeds.len(), MarketError::TooManyCreatorFeeds);
+
+        self.max_open_markets = params.max_open_markets;
+        self.allowed_feeds = params.allowed_feeds.clone();
+
+        Ok(())
+    }
+
+    //takes a slot of the quota for a new market settled on `feed_ids`, the primary and extra feeds.
+    //Event markets have no feed and are only open to creators without a feed restriction
+    pub fn open_market(&mut self, feed_ids: &[[u8; 32]]) -> Result<()> {
+        if !self.allowed_feeds.is_empty() {
+            require!(
+                !feed_ids.is_empty() && feed_ids.iter().all(|feed_id| self.allowed_feeds.contains(feed_id)),
+                MarketError::FeedNotAllowedForCreator
+            );
+        }
+        require_gt!(self.max_open_markets, self.open_markets, MarketError::CreatorQuotaReached);
+        self.open_markets += 1;
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreatorParams {
+    pub max_open_markets: u16,
+    pub allowed_feeds: Vec<[u8; 32]>,
+}
+
+//admin-allowed collateral mint, its bet limits are copied onto markets at initialize_pools
+#[account]
+#[derive(InitSpace)]
+pub struct CollateralConfig {
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub min_bet: u64,
+    pub max_bet: u64,
+    pub bump: u8,
+    pub creator_bond: u64, // posted by creators when they create a market on this mint
+    pub rent_payer: Pubkey, // refunded when the mint is removed
+}
+
+impl CollateralConfig {
+    pub fn apply(&mut self, params: &CollateralParams) -> Result<()> {
+        require!(
+            params.min_bet > 0 && params.min_bet <= params.max_bet,
+            MarketError::InvalidBetLimits
+        );
+
+        self.min_bet = params.min_bet;
+        self.max_bet = params.max_bet;
+        self.creator_bond = params.creator_bond;
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CollateralParams {
+    pub min_bet: u64, // in base units of the mint
+    pub max_bet: u64,
+    pub creator_bond: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FeedParams {
+    pub symbol: String,
+    pub oracle_source: OracleSource,
+    pub max_price_age_secs: u64,
+    pub max_conf_bps: u16,
+    pub enabled: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ConfigParams {
+    pub protocol_fee_bps: u16,
+    pub lp_fee_bps: u16,
+    pub max_creator_fee_bps: u16,
+    pub referral_share_bps: u16,
+    pub max_price_age_secs: u64,
+    pub max_settlement_window: u64,
+    pub arbiter: Pubkey,
+    pub trusted_mints: Vec<Pubkey>,
+    pub min_market_duration: u64,
+    pub market_lock_period: u64,
+    pub default_settlement_window: u64,
+    pub guardian: Pubkey,
+    pub permissioned_creation: bool,
+    pub bond_slash_delay: u64,
+    pub pool_seed_amount: u64,
+    pub arbitration_window: u64,
+}
+
+//actions stopped while set, on the config for every market or on a single market
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace, PartialEq, Eq)]
+pub struct PauseFlags {
+    pub betting: bool, // place_bet and add_liquidity
+    pub claiming: bool, // claims, liquidity withdrawals and every way a market is settled, voided or slashed
+    pub creation: bool, // initialize_market, initialize_event_market and initialize_pools
+}
+
+impl PauseFlags {
+    //every flag set in `other` is also set here
+    pub fn includes(&self, other: &PauseFlags) -> bool {
+        (self.betting || !other.betting) && (self.claiming || !other.claiming) && (self.creation || !other.creation)
+    }
+}
+
+//running total of the slots a pause flag was set
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace, PartialEq, Eq)]
+pub struct PausedSlots {
+    pub total: u64, // slots of the pauses that ended
+    pub since: Option<u64>, // start of the pause still on
+}
+
+impl PausedSlots {
+    //records the flag being set to `paused` at `slot`
+    pub fn update(&mut self, paused: bool, slot: u64) {
+        match (self.since, paused) {
+            (None, true) => self.since = Some(slot),
+            (Some(since), false) => {
+                self.total = self.total.saturating_add(slot.saturating_sub(since));
+                self.since = None;
+            }
+            _ => {}
+        }
+    }
+
+    //slots paused up to `slot`, including the pause still on
+    pub fn at(&self, slot: u64) -> u64 {
+        let current = self.since.map_or(0, |since| slot.saturating_sub(since));
+        self.total.saturating_add(current)
+    }
+}
+
+//per-market settings chosen by the creator at initialize_market, oracle settings come from the FeedRegistry
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MarketParams {
+    pub creator_fee_bps: u16,
+    pub fee_curve: Option<FeeCurve>,
+    pub target_exponent: i32,
+    pub settlement_window: Option<u64>,  // default_settlement_window of the config when None
+    pub min_pyth_signatures: Option<u8>, // fully verified pyth updates only when None
+    pub optimistic_resolution: Option<OptimisticResolution>,
+    pub extra_feed_ids: Vec<[u8; 32]>, // registered feeds settled on together with the market feed
+    pub min_agreeing_readings: u8, // 1 when there are no extra feeds
+    pub agreement_tolerance_bps: u16,
+    pub bet_limits: Option<BetLimits>, // limits of the collateral mint only when None
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BetQuote {
+    pub stake: u64,
+    pub odds: u64,
+    pub payout: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub lp_fee: u64,
+    pub dynamic_fee_bps: u16,
+    pub dynamic_fee: u64,
 }
@@ -1,6 +1,221 @@
+use anchor_lang::prelude::*;
 use anchor_lang::solana_program::hash::hash;
+use anchor_spl::token_interface::spl_token_2022::{
+    self,
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+};
+use anchor_lang::system_program;
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token_interface::{
+    close_account, sync_native, transfer_checked, CloseAccount, Mint, SyncNative, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+use pyth_solana_receiver_sdk::price_update::get_feed_id_from_hex;
+
+use crate::math;
+use crate::states::{CollateralConfig, Config, Market};
+use crate::MarketError;
+
+//extensions that let a third party move or intercept pool funds. Trusted mints with a transfer
+//hook still need a hook program that requires no extra accounts, none are passed on transfers
+const RESTRICTED_MINT_EXTENSIONS: [ExtensionType; 2] = [ExtensionType::PermanentDelegate, ExtensionType::TransferHook];
 
 //helper function to hash long string into the max seed length of 32
 pub fn hash_to_bytes(data: &[u8]) -> [u8; 32] {
     hash(data).to_bytes()
 }
+
+//accepts the 32 feed id bytes, or their hex encoding with or without the 0x prefix
+pub fn parse_feed_id(input: &[u8]) -> Result<[u8; 32]> {
+    if let Ok(feed_id) = <[u8; 32]>::try_from(input) {
+        return Ok(feed_id);
+    }
+
+    let hex = std::str::from_utf8(input).map_err(|_| MarketError::InvalidUtf8)?;
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    require_eq!(hex.len(), 64, MarketError::IncorrectFeedIDLength);
+
+    get_feed_id_from_hex(hex).map_err(|_| error!(MarketError::InvalidFeedId))
+}
+
+//rejects token-2022 mints with a restricted extension unless they are in `trusted_mints`
+pub fn check_mint_extensions(mint: &AccountInfo, trusted_mints: &[Pubkey]) -> Result<()> {
+    if *mint.owner != spl_token_2022::ID || trusted_mints.contains(mint.key) {
+        return Ok(());
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    let restricted = mint_state
+        .get_extension_types()?
+        .iter()
+        .any(|extension| RESTRICTED_MINT_EXTENSIONS.contains(extension));
+    require!(!restricted, MarketError::UnsupportedMintExtension);
+
+    Ok(())
+}
+
+//moves `amount` of collateral from `user` to `to`. Without a token account the bettor of a wrapped
+//SOL market pays lamports instead, which are wrapped in place by syncing `to`
+pub fn deposit_collateral<'info>(
+    token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    user: AccountInfo<'info>,
+    user_ata: Option<AccountInfo<'info>>,
+    to: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    match user_ata {
+        Some(user_ata) => transfer_checked(
+            CpiContext::new(
+                token_program,
+                TransferChecked {
+                    from: user_ata,
+                    mint: mint.to_account_info(),
+                    to,
+                    authority: user,
+                },
+            ),
+            amount,
+            mint.decimals,
+        ),
+        None => {
+            require_keys_eq!(mint.key(), native_mint::ID, MarketError::MissingUserTokenAccount);
+
+            system_program::transfer(
+                CpiContext::new(
+                    system_program,
+                    system_program::Transfer {
+                        from: user,
+                        to: to.clone(),
+                    },
+                ),
+                amount,
+            )?;
+
+            sync_native(CpiContext::new(token_program, SyncNative { account: to }))
+        }
+    }
+}
+
+//amount `account` gained since `balance_before`, mints with a transfer fee deliver less than was sent
+pub fn received_amount(account: &mut InterfaceAccount<TokenAccount>, balance_before: u64) -> Result<u64> {
+    account.reload()?;
+
+    math::checked_sub(account.amount, balance_before)
+}
+
+//posts the bond of the collateral mint into the bond vault of a new market, shared by
+//initialize_market and initialize_event_market. Returns the amount the vault received
+pub fn lock_creator_bond<'info>(
+    config: &Config,
+    collateral_config: &CollateralConfig,
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    creator_ata: &InterfaceAccount<'info, TokenAccount>,
+    creator: &Signer<'info>,
+    bond_vault: &mut InterfaceAccount<'info, TokenAccount>,
+) -> Result<u64> {
+    require_eq!(collateral_config.decimals, mint.decimals, MarketError::CollateralDecimalsMismatch);
+    check_mint_extensions(&mint.to_account_info(), &config.trusted_mints)?;
+
+    if collateral_config.creator_bond > 0 {
+        transfer_checked(
+            CpiContext::new(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: creator_ata.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: bond_vault.to_account_info(),
+                    authority: creator.to_account_info(),
+                },
+            ),
+            collateral_config.creator_bond,
+            mint.decimals,
+        )?;
+    }
+
+    received_amount(bond_vault, 0)
+}
+
+//empties the bond vault into `recipient` and closes it, shared by slash_creator_bond, finalize_market
+//and cancel_market
+pub fn release_bond_vault<'info>(
+    market: &Account<'info, Market>,
+    token_program: &Interface<'info, TokenInterface>,
+    bond_vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    recipient: &InterfaceAccount<'info, TokenAccount>,
+    rent_recipient: &AccountInfo<'info>,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        market.creator.as_ref(),
+        &market.feed_seed,
+        &market.target_price.to_le_bytes(),
+        &market.market_duration.to_le_bytes(),
+        &[market.bump],
+    ]];
+
+    if bond_vault.amount > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: bond_vault.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: recipient.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            bond_vault.amount,
+            mint.decimals,
+        )?;
+    }
+
+    close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: bond_vault.to_account_info(),
+            destination: rent_recipient.clone(),
+            authority: market.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED_HEX: &str = "ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
+
+    fn feed_bytes() -> [u8; 32] {
+        let mut feed_id = [0u8; 32];
+        for (i, byte) in feed_id.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&FEED_HEX[2 * i..2 * i + 2], 16).unwrap();
+        }
+        feed_id
+    }
+
+    #[test]
+    fn parse_feed_id_accepts_every_encoding() {
+        let expected = feed_bytes();
+
+        assert_eq!(parse_feed_id(&expected).unwrap(), expected);
+        assert_eq!(parse_feed_id(FEED_HEX.as_bytes()).unwrap(), expected);
+        assert_eq!(
+            parse_feed_id(format!("0x{FEED_HEX}").as_bytes()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn parse_feed_id_rejects_malformed_input() {
+        assert!(parse_feed_id(b"Invalid FeedId Length").is_err());
+        assert!(parse_feed_id(&[0u8; 31]).is_err());
+        assert!(parse_feed_id(format!("0y{FEED_HEX}").as_bytes()).is_err());
+        assert!(parse_feed_id(FEED_HEX.replace('e', "g").as_bytes()).is_err());
+    }
+}
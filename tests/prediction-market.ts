@@ -5,12 +5,25 @@ import { assert } from "chai";
 import { PredictionMarket } from "../target/types/prediction_market";
 import crypto, { generateKey, getCipherInfo, Sign } from "crypto";
 import * as token from "@solana/spl-token";
+import * as fs from "fs";
 import { PythSolanaReceiver } from "@pythnetwork/pyth-solana-receiver";
 
 const BET_SEED = "bet";
 const HIGHER_POOL_SEED = "higher_pool";
 const LOWER_POOL_SEED = "lower_pool";
-const USDC_MINT = "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU"; // Example USDC Mint address
+const LP_MINT_SEED = "lp_mint";
+const CONFIG_SEED = "config";
+const PROTOCOL_FEE_VAULT_SEED = "protocol_fee_vault";
+const CREATOR_FEE_VAULT_SEED = "creator_fee_vault";
+const REFERRER_SEED = "referrer";
+const REFERRAL_VAULT_SEED = "referral_vault";
+const MANUAL_ORACLE_SEED = "manual_oracle";
+const FEED_REGISTRY_SEED = "feed_registry";
+const RESOLUTION_SEED = "resolution";
+const RESOLUTION_VAULT_SEED = "resolution_vault";
+const COLLATERAL_CONFIG_SEED = "collateral_config";
+const CREATOR_REGISTRY_SEED = "creator_registry";
+const BOND_VAULT_SEED = "bond_vault";
 
 describe("prediction_market", () => {
   const provider = anchor.AnchorProvider.env();
@@ -22,107 +35,3765 @@ describe("prediction_market", () => {
   const feedIdString: string =
     "0xef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d";
   const feedIdString2: string = "Invalid FeedId Length";
+  const feedIdInput = Buffer.from(feedIdString);
+  const feedIdInput2 = Buffer.from(feedIdString2);
 
   const targetPrice: anchor.BN = new anchor.BN(140);
   const marketDuration: anchor.BN = new anchor.BN(1300);
   const marketCreator1 = anchor.web3.Keypair.generate();
 
   const hema = anchor.web3.Keypair.generate();
+  const liquidityProvider = anchor.web3.Keypair.generate();
+  const referrer = anchor.web3.Keypair.generate();
+  const guardian = anchor.web3.Keypair.generate();
   const mint_authority = anchor.web3.Keypair.generate();
 
   const to_mint = new anchor.BN(30000000);
 
-  const INITIAL_USDC_AMOUNT = program.idl.constants.find(
-    (el) => el.name == "initialUsdcPoolAmount"
-  ).value;
+  const creatorFeeBps = 50;
+  const feeCurve = { feeMinBps: 10, feeMaxBps: 500, rampBps: 2000 };
+  const marketParams = {
+    creatorFeeBps,
+    feeCurve,
+    targetExponent: 0,
+    settlementWindow: null,
+    minPythSignatures: null,
+    optimisticResolution: null,
+    extraFeedIds: [],
+    minAgreeingReadings: 1,
+    agreementToleranceBps: 0,
+    betLimits: null,
+  };
+  const feedParams = {
+    symbol: "SOL/USD",
+    oracleSource: { pythPull: {} },
+    maxPriceAgeSecs: new anchor.BN(30),
+    maxConfBps: 200,
+    enabled: true,
+  };
+  const collateralParams = {
+    minBet: new anchor.BN(1000),
+    maxBet: new anchor.BN(10_000_000),
+    creatorBond: new anchor.BN(100_000),
+  };
+  const configParams = {
+    protocolFeeBps: 100,
+    lpFeeBps: 30,
+    maxCreatorFeeBps: 200,
+    referralShareBps: 2000,
+    maxPriceAgeSecs: new anchor.BN(3600),
+    maxSettlementWindow: new anchor.BN(216000),
+    arbiter: provider.wallet.publicKey,
+    trustedMints: [],
+    minMarketDuration: new anchor.BN(1200),
+    marketLockPeriod: new anchor.BN(0),
+    defaultSettlementWindow: new anchor.BN(9000),
+    guardian: guardian.publicKey,
+    permissionedCreation: false,
+    bondSlashDelay: new anchor.BN(216000),
+    poolSeedAmount: new anchor.BN(1_000_000),
+    arbitrationWindow: new anchor.BN(9000),
+  };
+  const [configAddress] = getConfigAddress(program.programId);
+  const [feedRegistryAddress] = getFeedRegistryAddress(
+    feedIdString,
+    program.programId
+  );
+
+  const INITIAL_USDC_AMOUNT = configParams.poolSeedAmount.toNumber();
+  //collateral the creator bond of most test markets is posted in
+  let collateralMint: PublicKey;
+
+  //markets created by createShortMarket expire after this many slots
+  const shortDuration = new anchor.BN(60);
+
+  // Creates a manual oracle publishing in `exponent` and registers it as a
+  // feed with `params`, markets are listed on it with `feedIdString`
+  async function registerManualFeed(
+    label: string,
+    exponent: number,
+    params = {}
+  ) {
+    const authority = anchor.web3.Keypair.generate();
+    await airdrop(provider.connection, authority.publicKey);
+
+    const labelId = Array.from(
+      crypto.createHash("sha256").update(label, "utf-8").digest()
+    );
+    const [oracleAddress] = getManualOracleAddress(
+      authority.publicKey,
+      labelId,
+      program.programId
+    );
+    await program.methods
+      .initializeManualOracle(labelId, exponent)
+      .accountsStrict({
+        manualOracle: oracleAddress,
+        authority: authority.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc()
+      .then(confirmTx);
+
+    const feedIdString = "0x" + oracleAddress.toBuffer().toString("hex");
+    const [feedRegistry] = getFeedRegistryAddress(
+      feedIdString,
+      program.programId
+    );
+    await program.methods
+      .registerFeed(Array.from(oracleAddress.toBuffer()), {
+        ...feedParams,
+        symbol: label,
+        oracleSource: { manual: {} },
+        ...params,
+      })
+      .accountsStrict({
+        feedRegistry,
+        config: configAddress,
+        admin: provider.wallet.publicKey,
+        payer: provider.wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc()
+      .then(confirmTx);
+
+    return { authority, oracleAddress, feedIdString, feedRegistry };
+  }
+
+  type ManualFeed = Awaited<ReturnType<typeof registerManualFeed>>;
+
+  async function publishPrice(
+    feed: ManualFeed,
+    price: anchor.BN,
+    conf = new anchor.BN(0)
+  ) {
+    await program.methods
+      .updateManualOracle(price, conf)
+      .accountsStrict({
+        manualOracle: feed.oracleAddress,
+        authority: feed.authority.publicKey,
+      })
+      .signers([feed.authority])
+      .rpc()
+      .then(confirmTx);
+  }
+
+  // Creates a market of marketCreator1 on the registered `feed` that expires
+  // after shortDuration slots and seeds its pools from collateralMint
+  async function createShortMarket(
+    feed: Pick<ManualFeed, "feedIdString" | "feedRegistry">,
+    price: number,
+    params = {},
+    extraFeeds: ManualFeed[] = []
+  ) {
+    const shortTargetPrice = new anchor.BN(price);
+    const [marketAddress] = getMarketAddress(
+      marketCreator1.publicKey,
+      feed.feedIdString,
+      shortTargetPrice,
+      shortDuration,
+      program.programId
+    );
+
+    await program.methods
+      .updateConfig({ ...configParams, minMarketDuration: shortDuration })
+      .accountsStrict({
+        config: configAddress,
+        admin: provider.wallet.publicKey,
+      })
+      .rpc()
+      .then(confirmTx);
+
+    await program.methods
+      .initializeMarket(
+        shortTargetPrice,
+        Buffer.from(feed.feedIdString),
+        shortDuration,
+        {
+          ...marketParams,
+          feeCurve: null,
+          extraFeedIds: extraFeeds.map((extra) =>
+            Array.from(extra.oracleAddress.toBuffer())
+          ),
+          ...params,
+        }
+      )
+      .accountsStrict({
+        marketCreator: marketCreator1.publicKey,
+        market: marketAddress,
+        config: configAddress,
+        feedRegistry: feed.feedRegistry,
+        creatorRegistry: null,
+        ...getCreatorBondAccounts(
+          marketAddress,
+          marketCreator1.publicKey,
+          collateralMint,
+          program.programId
+        ),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(
+        extraFeeds.map((extra) => ({
+          pubkey: extra.feedRegistry,
+          isWritable: false,
+          isSigner: false,
+        }))
+      )
+      .signers([marketCreator1])
+      .rpc()
+      .then(confirmTx);
+
+    await program.methods
+      .updateConfig(configParams)
+      .accountsStrict({
+        config: configAddress,
+        admin: provider.wallet.publicKey,
+      })
+      .rpc()
+      .then(confirmTx);
+
+    await initializeShortPools(marketAddress);
+
+    return marketAddress;
+  }
+
+  // Seeds the pools of a market of marketCreator1 from collateralMint, topped
+  // up first so short markets do not drain the balance of other tests
+  async function initializeShortPools(marketAddress: PublicKey) {
+    await token.mintTo(
+      provider.connection,
+      mint_authority,
+      collateralMint,
+      token.getAssociatedTokenAddressSync(
+        collateralMint,
+        marketCreator1.publicKey
+      ),
+      mint_authority,
+      configParams.poolSeedAmount.muln(2).toNumber()
+    );
+    await program.methods
+      .initializePools()
+      .accountsStrict({
+        market: marketAddress,
+        marketCreator: marketCreator1.publicKey,
+        poolTokenMint: collateralMint,
+        collateralConfig: getCollateralConfigAddress(
+          collateralMint,
+          program.programId
+        )[0],
+        higherPool: getPoolAddress(
+          HIGHER_POOL_SEED,
+          marketAddress,
+          program.programId
+        )[0],
+        lowerPool: getPoolAddress(
+          LOWER_POOL_SEED,
+          marketAddress,
+          program.programId
+        )[0],
+        lpMint: getLpMintAddress(marketAddress, program.programId)[0],
+        config: configAddress,
+        protocolFeeVault: getProtocolFeeVaultAddress(
+          collateralMint,
+          program.programId
+        )[0],
+        creatorFeeVault: getCreatorFeeVaultAddress(
+          marketAddress,
+          program.programId
+        )[0],
+        userAta: token.getAssociatedTokenAddressSync(
+          collateralMint,
+          marketCreator1.publicKey
+        ),
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: token.TOKEN_PROGRAM_ID,
+      })
+      .signers([marketCreator1])
+      .rpc()
+      .then(confirmTx);
+  }
+
+  // Claims the bet of `user` on `marketAddress`, settling the market on
+  // `priceUpdate` if nobody did yet
+  async function claimShortBet(
+    marketAddress: PublicKey,
+    user: anchor.web3.Keypair,
+    betAddress: PublicKey,
+    priceUpdate: PublicKey
+  ) {
+    await program.methods
+      .claimBet()
+      .accountsStrict({
+        market: marketAddress,
+        config: configAddress,
+        higherPool: getPoolAddress(
+          HIGHER_POOL_SEED,
+          marketAddress,
+          program.programId
+        )[0],
+        lowerPool: getPoolAddress(
+          LOWER_POOL_SEED,
+          marketAddress,
+          program.programId
+        )[0],
+        userAta: token.getAssociatedTokenAddressSync(
+          collateralMint,
+          user.publicKey
+        ),
+        unwrapAccount: null,
+        user: user.publicKey,
+        bet: betAddress,
+        priceUpdate,
+        mint: collateralMint,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc()
+      .then(confirmTx);
+  }
+
+  // Funds `user` with collateralMint and bets `amount` on `marketAddress`
+  async function placeShortBet(
+    marketAddress: PublicKey,
+    user: anchor.web3.Keypair,
+    amount: anchor.BN,
+    direction: { higher: {} } | { lower: {} }
+  ) {
+    await airdrop(provider.connection, user.publicKey);
+    const userAta = await token.getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      collateralMint,
+      user.publicKey
+    );
+    await token.mintTo(
+      provider.connection,
+      mint_authority,
+      collateralMint,
+      userAta.address,
+      mint_authority,
+      amount.toNumber()
+    );
+
+    const [betAddress] = getBetAddress(
+      marketAddress,
+      user.publicKey,
+      amount,
+      direction,
+      program.programId
+    );
+    await program.methods
+      .placeBet(amount, direction)
+      .accountsStrict({
+        bet: betAddress,
+        market: marketAddress,
+        config: configAddress,
+        protocolFeeVault: getProtocolFeeVaultAddress(
+          collateralMint,
+          program.programId
+        )[0],
+        creatorFeeVault: getCreatorFeeVaultAddress(
+          marketAddress,
+          program.programId
+        )[0],
+        referrerStats: null,
+        referralVault: null,
+        user: user.publicKey,
+        higherPool: getPoolAddress(
+          HIGHER_POOL_SEED,
+          marketAddress,
+          program.programId
+        )[0],
+        lowerPool: getPoolAddress(
+          LOWER_POOL_SEED,
+          marketAddress,
+          program.programId
+        )[0],
+        userAta: userAta.address,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        mint: collateralMint,
+        tokenProgram: token.TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc()
+      .then(confirmTx);
+
+    return betAddress;
+  }
+
+  // Waits until `slot` has passed
+  async function waitForSlot(slot: number) {
+    while ((await provider.connection.getSlot("confirmed")) <= slot) {
+      await new Promise((resolve) => setTimeout(resolve, 400));
+    }
+  }
+
+  // Waits until the market expired, prices published afterwards are also
+  // past its expiry in unix time
+  async function waitForExpiry(marketAddress: PublicKey) {
+    const market = await program.account.market.fetch(marketAddress);
+    await waitForSlot(market.startTime.add(market.marketDuration).toNumber());
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+  }
+
+  // Settles a market on `priceUpdate` and the price accounts of its extra
+  // feeds, in market order
+  async function settleShortMarket(
+    marketAddress: PublicKey,
+    priceUpdate: PublicKey,
+    extraPriceUpdates: PublicKey[] = []
+  ) {
+    await program.methods
+      .settleMarket()
+      .accountsStrict({
+        market: marketAddress,
+        config: configAddress,
+        priceUpdate,
+      })
+      .remainingAccounts(
+        extraPriceUpdates.map((pubkey) => ({
+          pubkey,
+          isWritable: false,
+          isSigner: false,
+        }))
+      )
+      .rpc()
+      .then(confirmTx);
+  }
+
+  // Runs `call` and checks it fails with the program error `code`
+  async function expectProgramError(call: Promise<unknown>, code: string) {
+    let should_fail = "This Should Fail";
+    try {
+      await call;
+    } catch (e) {
+      const anchorErr = anchor.AnchorError.parse(e.logs);
+      assert.strictEqual(
+        anchorErr.error.errorCode.code,
+        code,
+        "Unexpected Error Code"
+      );
+      should_fail = "Failed";
+    }
+    assert.strictEqual(should_fail, "Failed");
+  }
+
+  describe("Config Initialization", () => {
+    it("Initializes the protocol config", async () => {
+      const [programDataAddress] = PublicKey.findProgramAddressSync(
+        [program.programId.toBuffer()],
+        anchor.web3.BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+      );
+
+      await program.methods
+        .initializeConfig(configParams)
+        .accountsStrict({
+          config: configAddress,
+          program: program.programId,
+          programData: programDataAddress,
+          admin: provider.wallet.publicKey,
+          payer: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      const config = await program.account.config.fetch(configAddress);
+      assert.strictEqual(
+        config.admin.toString(),
+        provider.wallet.publicKey.toString()
+      );
+      assert.strictEqual(config.protocolFeeBps, configParams.protocolFeeBps);
+      assert.strictEqual(config.lpFeeBps, configParams.lpFeeBps);
+      assert.strictEqual(
+        config.maxCreatorFeeBps,
+        configParams.maxCreatorFeeBps
+      );
+      assert.strictEqual(
+        config.minMarketDuration.toString(),
+        configParams.minMarketDuration.toString()
+      );
+    });
+
+    it("Adds the collateral mint of the test markets", async () => {
+      await airdrop(provider.connection, mint_authority.publicKey);
+      await airdrop(provider.connection, marketCreator1.publicKey);
+
+      collateralMint = await token.createMint(
+        provider.connection,
+        mint_authority,
+        mint_authority.publicKey,
+        null,
+        6
+      );
+      await addCollateralMint(program, collateralMint, collateralParams);
+
+      const creatorAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        marketCreator1,
+        collateralMint,
+        marketCreator1.publicKey
+      );
+      await token.mintTo(
+        provider.connection,
+        mint_authority,
+        collateralMint,
+        creatorAta.address,
+        mint_authority,
+        to_mint.toNumber()
+      );
+    });
+  });
+
+  describe("Admin Transfer", () => {
+    const newAdmin = anchor.web3.Keypair.generate();
+
+    it("Can not accept an admin transfer that was not proposed", async () => {
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .acceptAdmin()
+          .accountsStrict({
+            config: configAddress,
+            pendingAdmin: newAdmin.publicKey,
+          })
+          .signers([newAdmin])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "UnauthorizedUser",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+
+    it("Transfers the admin in two steps and back", async () => {
+      await program.methods
+        .proposeAdmin(newAdmin.publicKey)
+        .accountsStrict({
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      let config = await program.account.config.fetch(configAddress);
+      assert.strictEqual(
+        config.admin.toString(),
+        provider.wallet.publicKey.toString()
+      );
+      assert.strictEqual(
+        config.pendingAdmin.toString(),
+        newAdmin.publicKey.toString()
+      );
+
+      await program.methods
+        .acceptAdmin()
+        .accountsStrict({
+          config: configAddress,
+          pendingAdmin: newAdmin.publicKey,
+        })
+        .signers([newAdmin])
+        .rpc()
+        .then(confirmTx);
+
+      config = await program.account.config.fetch(configAddress);
+      assert.strictEqual(
+        config.admin.toString(),
+        newAdmin.publicKey.toString()
+      );
+      assert.isNull(config.pendingAdmin);
+
+      //the remaining tests run with the provider wallet as admin
+      await program.methods
+        .proposeAdmin(provider.wallet.publicKey)
+        .accountsStrict({
+          config: configAddress,
+          admin: newAdmin.publicKey,
+        })
+        .signers([newAdmin])
+        .rpc()
+        .then(confirmTx);
+      await program.methods
+        .acceptAdmin()
+        .accountsStrict({
+          config: configAddress,
+          pendingAdmin: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+    });
+  });
+
+  describe("Feed Registration", () => {
+    it("Registers the market feed", async () => {
+      await program.methods
+        .registerFeed(Array.from(feedIdToBytes(feedIdString)), feedParams)
+        .accountsStrict({
+          feedRegistry: feedRegistryAddress,
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+          payer: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      const feed = await program.account.feedRegistry.fetch(
+        feedRegistryAddress
+      );
+      assert.strictEqual(feed.symbol, feedParams.symbol);
+      assert.strictEqual(feed.maxConfBps, feedParams.maxConfBps);
+      assert.isTrue(feed.enabled);
+    });
+  });
+
+  describe("Manual Oracle", () => {
+    const oracleAuthority = anchor.web3.Keypair.generate();
+    const oracleFeedId = Array.from(
+      crypto.createHash("sha256").update("SOL/USD", "utf-8").digest()
+    );
+    const [manualOracleAddress] = getManualOracleAddress(
+      oracleAuthority.publicKey,
+      oracleFeedId,
+      program.programId
+    );
+
+    it("Initializes a manual oracle", async () => {
+      await airdrop(provider.connection, oracleAuthority.publicKey);
+
+      await program.methods
+        .initializeManualOracle(oracleFeedId, -8)
+        .accountsStrict({
+          manualOracle: manualOracleAddress,
+          authority: oracleAuthority.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([oracleAuthority])
+        .rpc()
+        .then(confirmTx);
+
+      const oracle = await program.account.manualOracle.fetch(
+        manualOracleAddress
+      );
+      assert.strictEqual(
+        oracle.authority.toString(),
+        oracleAuthority.publicKey.toString()
+      );
+      assert.strictEqual(oracle.exponent, -8);
+      assert.strictEqual(oracle.publishTime.toString(), "0");
+    });
+
+    it("Authority publishes a price", async () => {
+      const price = new anchor.BN(14_000_000_000);
+
+      await program.methods
+        .updateManualOracle(price, new anchor.BN(1_000_000))
+        .accountsStrict({
+          manualOracle: manualOracleAddress,
+          authority: oracleAuthority.publicKey,
+        })
+        .signers([oracleAuthority])
+        .rpc()
+        .then(confirmTx);
+
+      const oracle = await program.account.manualOracle.fetch(
+        manualOracleAddress
+      );
+      assert.strictEqual(oracle.price.toString(), price.toString());
+      assert.strictEqual(oracle.conf.toString(), "1000000");
+      assert.notStrictEqual(oracle.publishTime.toString(), "0");
+    });
+
+    it("Publishes a price with exponent -5", async () => {
+      const feedId = Array.from(
+        crypto.createHash("sha256").update("ETH/USD", "utf-8").digest()
+      );
+      const [oracleAddress] = getManualOracleAddress(
+        oracleAuthority.publicKey,
+        feedId,
+        program.programId
+      );
+      const price = new anchor.BN(345_012_345);
+
+      await program.methods
+        .initializeManualOracle(feedId, -5)
+        .accountsStrict({
+          manualOracle: oracleAddress,
+          authority: oracleAuthority.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([oracleAuthority])
+        .rpc()
+        .then(confirmTx);
+
+      await program.methods
+        .updateManualOracle(price, new anchor.BN(10_000))
+        .accountsStrict({
+          manualOracle: oracleAddress,
+          authority: oracleAuthority.publicKey,
+        })
+        .signers([oracleAuthority])
+        .rpc()
+        .then(confirmTx);
+
+      const oracle = await program.account.manualOracle.fetch(oracleAddress);
+      assert.strictEqual(oracle.exponent, -5);
+      assert.strictEqual(oracle.price.toString(), price.toString());
+    });
+
+    it("Can not publish a non-positive price", async () => {
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .updateManualOracle(new anchor.BN(-1), new anchor.BN(0))
+          .accountsStrict({
+            manualOracle: manualOracleAddress,
+            authority: oracleAuthority.publicKey,
+          })
+          .signers([oracleAuthority])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "NonPositivePrice",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+
+    it("Can not publish from another signer", async () => {
+      await airdrop(provider.connection, hema.publicKey);
+
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .updateManualOracle(new anchor.BN(1), new anchor.BN(0))
+          .accountsStrict({
+            manualOracle: manualOracleAddress,
+            authority: hema.publicKey,
+          })
+          .signers([hema])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+  });
+
+  describe("Market Initialization", () => {
+    it("Initializes a market", async () => {
+      await airdrop(provider.connection, marketCreator1.publicKey);
+
+      const [marketAddress, marketBump] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+
+      await program.methods
+        .initializeMarket(
+          targetPrice,
+          feedIdInput,
+          marketDuration,
+          marketParams
+        )
+        .accountsStrict({
+          market: marketAddress,
+          marketCreator: marketCreator1.publicKey,
+          config: configAddress,
+          feedRegistry: feedRegistryAddress,
+          creatorRegistry: null,
+          ...getCreatorBondAccounts(
+            marketAddress,
+            marketCreator1.publicKey,
+            collateralMint,
+            program.programId
+          ),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      await checkMarket(
+        program,
+        marketAddress,
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        marketBump,
+        { initializedMarket: {} }
+      );
+
+      const market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(market.maxConfBps, feedParams.maxConfBps);
+      assert.strictEqual(market.targetExponent, marketParams.targetExponent);
+      assert.strictEqual(market.maxPriceAgeSecs.toString(), "30");
+      assert.strictEqual(market.settlementWindow.toString(), "9000");
+      assert.isNull(market.minPythSignatures);
+      assert.isNull(market.finalConf);
+      assert.isFalse(market.voided);
+    });
+    it("Can not initialize with invalid FeedId", async () => {
+      await airdrop(provider.connection, marketCreator1.publicKey);
+
+      let should_fail = "This Should Fail";
+      try {
+        const [marketAddress, marketBump] = getMarketAddress(
+          marketCreator1.publicKey,
+          feedIdString2,
+          targetPrice,
+          marketDuration,
+          program.programId
+        );
+
+        await program.methods
+          .initializeMarket(
+            targetPrice,
+            feedIdInput2,
+            marketDuration,
+            { ...marketParams, feeCurve: null }
+          )
+          .accountsStrict({
+            marketCreator: marketCreator1.publicKey,
+            market: marketAddress,
+            config: configAddress,
+            feedRegistry: getFeedRegistryAddress(
+              feedIdString2,
+              program.programId
+            )[0],
+            creatorRegistry: null,
+            ...getCreatorBondAccounts(
+              marketAddress,
+              marketCreator1.publicKey,
+              collateralMint,
+              program.programId
+            ),
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([marketCreator1])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        // an unparseable feed id has no registry entry
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "AccountNotInitialized",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+    it("Initializes a market from raw feed id bytes", async () => {
+      const otherTargetPrice = new anchor.BN(180);
+      const [marketAddress, marketBump] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        otherTargetPrice,
+        marketDuration,
+        program.programId
+      );
+
+      await program.methods
+        .initializeMarket(
+          otherTargetPrice,
+          feedIdToBytes(feedIdString),
+          marketDuration,
+          { ...marketParams, feeCurve: null }
+        )
+        .accountsStrict({
+          marketCreator: marketCreator1.publicKey,
+          market: marketAddress,
+          config: configAddress,
+          feedRegistry: feedRegistryAddress,
+          creatorRegistry: null,
+          ...getCreatorBondAccounts(
+            marketAddress,
+            marketCreator1.publicKey,
+            collateralMint,
+            program.programId
+          ),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      await checkMarket(
+        program,
+        marketAddress,
+        marketCreator1.publicKey,
+        feedIdString,
+        otherTargetPrice,
+        marketDuration,
+        marketBump,
+        { initializedMarket: {} }
+      );
+
+      const market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(
+        market.feedSeed.toString(),
+        Array.from(feedIdToBytes(feedIdString)).toString()
+      );
+      assert.strictEqual(Object.keys(market.feedVersion)[0], "bytes");
+    });
+
+    it("Can not initialize a market expiring past the last slot", async () => {
+      const longDuration = new anchor.BN(2).pow(new anchor.BN(64)).subn(1);
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        longDuration,
+        program.programId
+      );
+
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .initializeMarket(
+            targetPrice,
+            feedIdInput,
+            longDuration,
+            { ...marketParams, feeCurve: null }
+          )
+          .accountsStrict({
+            marketCreator: marketCreator1.publicKey,
+            market: marketAddress,
+            config: configAddress,
+            feedRegistry: feedRegistryAddress,
+            creatorRegistry: null,
+            ...getCreatorBondAccounts(
+              marketAddress,
+              marketCreator1.publicKey,
+              collateralMint,
+              program.programId
+            ),
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([marketCreator1])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "MathOverflow",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+
+    it("Guardian pauses market creation and admin lifts it", async () => {
+      const pausedTargetPrice = new anchor.BN(190);
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        pausedTargetPrice,
+        marketDuration,
+        program.programId
+      );
+
+      const noPause = { betting: false, claiming: false, creation: false };
+      await program.methods
+        .setProtocolPause({ ...noPause, creation: true })
+        .accountsStrict({
+          config: configAddress,
+          authority: guardian.publicKey,
+        })
+        .signers([guardian])
+        .rpc()
+        .then(confirmTx);
+
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .initializeMarket(
+            pausedTargetPrice,
+            feedIdInput,
+            marketDuration,
+            { ...marketParams, feeCurve: null }
+          )
+          .accountsStrict({
+            marketCreator: marketCreator1.publicKey,
+            market: marketAddress,
+            config: configAddress,
+            feedRegistry: feedRegistryAddress,
+            creatorRegistry: null,
+            ...getCreatorBondAccounts(
+              marketAddress,
+              marketCreator1.publicKey,
+              collateralMint,
+              program.programId
+            ),
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([marketCreator1])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "ActionPaused",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+
+      //only the admin can lift a pause
+      should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .setProtocolPause(noPause)
+          .accountsStrict({
+            config: configAddress,
+            authority: guardian.publicKey,
+          })
+          .signers([guardian])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "UnauthorizedUser",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+
+      await program.methods
+        .setProtocolPause(noPause)
+        .accountsStrict({
+          config: configAddress,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      const config = await program.account.config.fetch(configAddress);
+      assert.isFalse(config.pause.creation);
+    });
+  });
+
+  describe("Feed Registry", () => {
+    const eurFeedIdString =
+      "0xa995d00bb36a63cef7fd2c287dc105fc8f3d93779f062f09551b0af3e81ec30b";
+    const eurFeedId = Array.from(feedIdToBytes(eurFeedIdString));
+    const [eurFeedRegistryAddress] = getFeedRegistryAddress(
+      eurFeedIdString,
+      program.programId
+    );
+    const eurFeedParams = {
+      ...feedParams,
+      symbol: "EUR/USD",
+      maxPriceAgeSecs: new anchor.BN(3600),
+    };
+
+    it("Registers a slow-moving feed with looser limits", async () => {
+      await program.methods
+        .registerFeed(eurFeedId, eurFeedParams)
+        .accountsStrict({
+          feedRegistry: eurFeedRegistryAddress,
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+          payer: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      const otherTargetPrice = new anchor.BN(160);
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        eurFeedIdString,
+        otherTargetPrice,
+        marketDuration,
+        program.programId
+      );
+      const settlementWindow = new anchor.BN(72000);
+
+      await program.methods
+        .initializeMarket(
+          otherTargetPrice,
+          Buffer.from(eurFeedIdString),
+          marketDuration,
+          { ...marketParams, feeCurve: null, settlementWindow }
+        )
+        .accountsStrict({
+          marketCreator: marketCreator1.publicKey,
+          market: marketAddress,
+          config: configAddress,
+          feedRegistry: eurFeedRegistryAddress,
+          creatorRegistry: null,
+          ...getCreatorBondAccounts(
+            marketAddress,
+            marketCreator1.publicKey,
+            collateralMint,
+            program.programId
+          ),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      const market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(
+        market.maxPriceAgeSecs.toString(),
+        eurFeedParams.maxPriceAgeSecs.toString()
+      );
+      assert.strictEqual(
+        market.settlementWindow.toString(),
+        settlementWindow.toString()
+      );
+    });
+
+    it("Can not list a market on a disabled feed", async () => {
+      await program.methods
+        .updateFeed({ ...eurFeedParams, enabled: false })
+        .accountsStrict({
+          feedRegistry: eurFeedRegistryAddress,
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      const otherTargetPrice = new anchor.BN(170);
+
+      let should_fail = "This Should Fail";
+      try {
+        const [marketAddress] = getMarketAddress(
+          marketCreator1.publicKey,
+          eurFeedIdString,
+          otherTargetPrice,
+          marketDuration,
+          program.programId
+        );
+
+        await program.methods
+          .initializeMarket(
+            otherTargetPrice,
+            Buffer.from(eurFeedIdString),
+            marketDuration,
+            { ...marketParams, feeCurve: null }
+          )
+          .accountsStrict({
+            marketCreator: marketCreator1.publicKey,
+            market: marketAddress,
+            config: configAddress,
+            feedRegistry: eurFeedRegistryAddress,
+            creatorRegistry: null,
+            ...getCreatorBondAccounts(
+              marketAddress,
+              marketCreator1.publicKey,
+              collateralMint,
+              program.programId
+            ),
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([marketCreator1])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "FeedDisabled",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+
+    it("Can not register a feed without a confidence limit", async () => {
+      const feedId = Array.from(crypto.randomBytes(32));
+      const [feedRegistryAddress] = PublicKey.findProgramAddressSync(
+        [
+          anchor.utils.bytes.utf8.encode(FEED_REGISTRY_SEED),
+          Buffer.from(feedId),
+        ],
+        program.programId
+      );
+
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .registerFeed(feedId, { ...feedParams, maxConfBps: 0 })
+          .accountsStrict({
+            feedRegistry: feedRegistryAddress,
+            config: configAddress,
+            admin: provider.wallet.publicKey,
+            payer: provider.wallet.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "InvalidConfidenceLimit",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+
+    it("Can not exceed the protocol staleness limit", async () => {
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .updateFeed({
+            ...eurFeedParams,
+            maxPriceAgeSecs: configParams.maxPriceAgeSecs.addn(1),
+          })
+          .accountsStrict({
+            feedRegistry: eurFeedRegistryAddress,
+            config: configAddress,
+            admin: provider.wallet.publicKey,
+          })
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "InvalidMaxPriceAge",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+  });
+
+  describe("Multi-Oracle Market", () => {
+    const solFeedIdString = "0x" + crypto.randomBytes(32).toString("hex");
+    const solFeedId = Array.from(feedIdToBytes(solFeedIdString));
+    const [solFeedRegistryAddress] = getFeedRegistryAddress(
+      solFeedIdString,
+      program.programId
+    );
+    const multiOracleParams = {
+      ...marketParams,
+      feeCurve: null,
+      extraFeedIds: [solFeedId],
+      minAgreeingReadings: 2,
+      agreementToleranceBps: 50,
+    };
+
+    it("Initializes a market settled on two feeds", async () => {
+      await program.methods
+        .registerFeed(solFeedId, {
+          ...feedParams,
+          oracleSource: { switchboardOnDemand: {} },
+        })
+        .accountsStrict({
+          feedRegistry: solFeedRegistryAddress,
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+          payer: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      const otherTargetPrice = new anchor.BN(180);
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        otherTargetPrice,
+        marketDuration,
+        program.programId
+      );
+
+      await program.methods
+        .initializeMarket(
+          otherTargetPrice,
+          feedIdInput,
+          marketDuration,
+          multiOracleParams
+        )
+        .accountsStrict({
+          marketCreator: marketCreator1.publicKey,
+          market: marketAddress,
+          config: configAddress,
+          feedRegistry: feedRegistryAddress,
+          creatorRegistry: null,
+          ...getCreatorBondAccounts(
+            marketAddress,
+            marketCreator1.publicKey,
+            collateralMint,
+            program.programId
+          ),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .remainingAccounts([
+          {
+            pubkey: solFeedRegistryAddress,
+            isWritable: false,
+            isSigner: false,
+          },
+        ])
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      const market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(market.extraFeeds.length, 1);
+      assert.deepEqual(market.extraFeeds[0].feedId, solFeedId);
+      assert.deepEqual(market.extraFeeds[0].source, {
+        switchboardOnDemand: {},
+      });
+      assert.strictEqual(market.minAgreeingReadings, 2);
+      assert.strictEqual(market.agreementToleranceBps, 50);
+      assert.strictEqual(market.settlementReadings.length, 0);
+    });
+
+    it("Can not require more agreeing readings than feeds", async () => {
+      const otherTargetPrice = new anchor.BN(190);
+
+      let should_fail = "This Should Fail";
+      try {
+        const [marketAddress] = getMarketAddress(
+          marketCreator1.publicKey,
+          feedIdString,
+          otherTargetPrice,
+          marketDuration,
+          program.programId
+        );
+
+        await program.methods
+          .initializeMarket(
+            otherTargetPrice,
+            feedIdInput,
+            marketDuration,
+            { ...multiOracleParams, minAgreeingReadings: 3 }
+          )
+          .accountsStrict({
+            marketCreator: marketCreator1.publicKey,
+            market: marketAddress,
+            config: configAddress,
+            feedRegistry: feedRegistryAddress,
+            creatorRegistry: null,
+            ...getCreatorBondAccounts(
+              marketAddress,
+              marketCreator1.publicKey,
+              collateralMint,
+              program.programId
+            ),
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .remainingAccounts([
+            {
+              pubkey: solFeedRegistryAddress,
+              isWritable: false,
+              isSigner: false,
+            },
+          ])
+          .signers([marketCreator1])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "InvalidOracleAgreement",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+  });
+
+  describe("Event Market", () => {
+    const eventId = Array.from(
+      crypto
+        .createHash("sha256")
+        .update("Will proposal X pass?", "utf-8")
+        .digest()
+    );
+    const resolver = anchor.web3.Keypair.generate();
+
+    it("Initializes an event market", async () => {
+      const [marketAddress] = getEventMarketAddress(
+        marketCreator1.publicKey,
+        eventId,
+        marketDuration,
+        program.programId
+      );
+
+      await program.methods
+        .initializeEventMarket(eventId, marketDuration, resolver.publicKey, {
+          ...marketParams,
+          feeCurve: null,
+        })
+        .accountsStrict({
+          market: marketAddress,
+          config: configAddress,
+          marketCreator: marketCreator1.publicKey,
+          creatorRegistry: null,
+          ...getCreatorBondAccounts(
+            marketAddress,
+            marketCreator1.publicKey,
+            collateralMint,
+            program.programId
+          ),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      const market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(Object.keys(market.kind)[0], "event");
+      assert.strictEqual(
+        market.resolver.toString(),
+        resolver.publicKey.toString()
+      );
+      assert.isNull(market.eventOutcome);
+      assert.strictEqual(market.targetPrice.toString(), "0");
+    });
+
+    it("Can not resolve a price market", async () => {
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .resolveMarket(true)
+          .accountsStrict({
+            market: marketAddress,
+            config: configAddress,
+            resolver: resolver.publicKey,
+          })
+          .signers([resolver])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "InvalidMarketKind",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+
+    // Creates an event market of marketCreator1 on `question` that expires
+    // after shortDuration slots, with pools seeded from collateralMint
+    async function createShortEventMarket(question: string, params = {}) {
+      const questionId = Array.from(
+        crypto.createHash("sha256").update(question, "utf-8").digest()
+      );
+      const [marketAddress] = getEventMarketAddress(
+        marketCreator1.publicKey,
+        questionId,
+        shortDuration,
+        program.programId
+      );
+
+      await program.methods
+        .updateConfig({ ...configParams, minMarketDuration: shortDuration })
+        .accountsStrict({
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      await program.methods
+        .initializeEventMarket(questionId, shortDuration, resolver.publicKey, {
+          ...marketParams,
+          feeCurve: null,
+          ...params,
+        })
+        .accountsStrict({
+          market: marketAddress,
+          config: configAddress,
+          marketCreator: marketCreator1.publicKey,
+          creatorRegistry: null,
+          ...getCreatorBondAccounts(
+            marketAddress,
+            marketCreator1.publicKey,
+            collateralMint,
+            program.programId
+          ),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      await program.methods
+        .updateConfig(configParams)
+        .accountsStrict({
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      await initializeShortPools(marketAddress);
+      return marketAddress;
+    }
+
+    async function collateralBalance(user: anchor.web3.Keypair) {
+      const account = await token.getAccount(
+        provider.connection,
+        token.getAssociatedTokenAddressSync(collateralMint, user.publicKey),
+        "confirmed"
+      );
+      return new anchor.BN(account.amount.toString());
+    }
+
+    function resolve(marketAddress: PublicKey, signer: anchor.web3.Keypair) {
+      return program.methods
+        .resolveMarket(true)
+        .accountsStrict({
+          market: marketAddress,
+          config: configAddress,
+          resolver: signer.publicKey,
+        })
+        .signers([signer])
+        .rpc();
+    }
+
+    it("Pays the yes bets of an event the resolver says happened", async () => {
+      const marketAddress = await createShortEventMarket("Will A happen?");
+      const yesBettor = anchor.web3.Keypair.generate();
+      const noBettor = anchor.web3.Keypair.generate();
+      const stake = new anchor.BN(1_000_000);
+      const yesBet = await placeShortBet(marketAddress, yesBettor, stake, {
+        higher: {},
+      });
+      const noBet = await placeShortBet(marketAddress, noBettor, stake, {
+        lower: {},
+      });
+      await waitForExpiry(marketAddress);
+
+      await resolve(marketAddress, resolver).then(confirmTx);
+
+      const market = await program.account.market.fetch(marketAddress);
+      assert.isTrue(market.eventOutcome);
+
+      //event markets have no price account, any account can be passed
+      const noPrice = anchor.web3.SystemProgram.programId;
+      const bet = await program.account.bet.fetch(yesBet);
+      const payout = bet.stake.mul(bet.odds).divn(1_000_000);
+      const yesBefore = await collateralBalance(yesBettor);
+      await claimShortBet(marketAddress, yesBettor, yesBet, noPrice);
+      assert.strictEqual(
+        (await collateralBalance(yesBettor)).sub(yesBefore).toString(),
+        payout.toString()
+      );
+
+      const noBefore = await collateralBalance(noBettor);
+      await claimShortBet(marketAddress, noBettor, noBet, noPrice);
+      assert.strictEqual(
+        (await collateralBalance(noBettor)).toString(),
+        noBefore.toString()
+      );
+      const lost = await program.account.bet.fetch(noBet);
+      assert.isTrue(lost.claimed);
+      assert.isFalse(lost.isWon);
+    });
+
+    it("Can not resolve an event as someone else", async () => {
+      const marketAddress = await createShortEventMarket("Will B happen?");
+      await waitForExpiry(marketAddress);
+
+      const impostor = anchor.web3.Keypair.generate();
+      await airdrop(provider.connection, impostor.publicKey);
+      await expectProgramError(
+        resolve(marketAddress, impostor),
+        "UnauthorizedUser"
+      );
+
+      const market = await program.account.market.fetch(marketAddress);
+      assert.isNull(market.eventOutcome);
+    });
+
+    it("Voids and refunds an event left unresolved", async () => {
+      const marketAddress = await createShortEventMarket("Will C happen?", {
+        settlementWindow: new anchor.BN(10),
+      });
+      const bettor = anchor.web3.Keypair.generate();
+      const stake = new anchor.BN(1_000_000);
+      const betAddress = await placeShortBet(marketAddress, bettor, stake, {
+        higher: {},
+      });
+
+      const market = await program.account.market.fetch(marketAddress);
+      const deadline = market.startTime
+        .add(market.marketDuration)
+        .add(market.settlementWindow);
+      await waitForSlot(deadline.toNumber());
+
+      //the resolver is too late, the first claim voids the market
+      await expectProgramError(
+        resolve(marketAddress, resolver),
+        "ResolutionDeadlineOver"
+      );
+      const bet = await program.account.bet.fetch(betAddress);
+      const before = await collateralBalance(bettor);
+      await claimShortBet(
+        marketAddress,
+        bettor,
+        betAddress,
+        anchor.web3.SystemProgram.programId
+      );
+
+      const voided = await program.account.market.fetch(marketAddress);
+      assert.isTrue(voided.voided);
+      assert.isTrue(voided.voidedByTimeout);
+      assert.strictEqual(
+        (await collateralBalance(bettor)).sub(before).toString(),
+        bet.stake.toString()
+      );
+    });
+  });
+
+  describe("Creator Registry", () => {
+    const registeredCreator = anchor.web3.Keypair.generate();
+    const [creatorRegistryAddress] = getCreatorRegistryAddress(
+      registeredCreator.publicKey,
+      program.programId
+    );
+
+    async function initializeCreatorMarket(
+      creator: anchor.web3.Keypair,
+      price: number,
+      creatorRegistry: PublicKey | null
+    ) {
+      const creatorTargetPrice = new anchor.BN(price);
+      const [marketAddress] = getMarketAddress(
+        creator.publicKey,
+        feedIdString,
+        creatorTargetPrice,
+        marketDuration,
+        program.programId
+      );
+      await program.methods
+        .initializeMarket(
+          creatorTargetPrice,
+          feedIdInput,
+          marketDuration,
+          { ...marketParams, feeCurve: null }
+        )
+        .accountsStrict({
+          marketCreator: creator.publicKey,
+          market: marketAddress,
+          config: configAddress,
+          feedRegistry: feedRegistryAddress,
+          creatorRegistry,
+          ...getCreatorBondAccounts(
+            marketAddress,
+            creator.publicKey,
+            collateralMint,
+            program.programId
+          ),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc()
+        .then(confirmTx);
+    }
+
+    it("Registers a creator with a quota of one market", async () => {
+      await airdrop(provider.connection, registeredCreator.publicKey);
+
+      const creatorAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        registeredCreator,
+        collateralMint,
+        registeredCreator.publicKey
+      );
+      await token.mintTo(
+        provider.connection,
+        mint_authority,
+        collateralMint,
+        creatorAta.address,
+        mint_authority,
+        to_mint.toNumber()
+      );
+
+      await program.methods
+        .registerCreator(registeredCreator.publicKey, {
+          maxOpenMarkets: 1,
+          allowedFeeds: [Array.from(feedIdToBytes(feedIdString))],
+        })
+        .accountsStrict({
+          creatorRegistry: creatorRegistryAddress,
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+          payer: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      await program.methods
+        .updateConfig({ ...configParams, permissionedCreation: true })
+        .accountsStrict({
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+    });
+
+    it("Can not create a market without a registry entry", async () => {
+      await airdrop(provider.connection, marketCreator1.publicKey);
+      await expectProgramError(
+        initializeCreatorMarket(marketCreator1, 300, null),
+        "CreatorNotRegistered"
+      );
+    });
+
+    it("Registered creator creates markets within its quota", async () => {
+      await initializeCreatorMarket(
+        registeredCreator,
+        300,
+        creatorRegistryAddress
+      );
+
+      const registry = await program.account.creatorRegistry.fetch(
+        creatorRegistryAddress
+      );
+      assert.strictEqual(registry.openMarkets, 1);
+
+      await expectProgramError(
+        initializeCreatorMarket(
+          registeredCreator,
+          310,
+          creatorRegistryAddress
+        ),
+        "CreatorQuotaReached"
+      );
+    });
+
+    it("Creator restricted to feeds can not create event markets", async () => {
+      const eventId = Array.from(
+        crypto.createHash("sha256").update("Restricted event").digest()
+      );
+      const [marketAddress] = getEventMarketAddress(
+        registeredCreator.publicKey,
+        eventId,
+        marketDuration,
+        program.programId
+      );
+
+      await expectProgramError(
+        program.methods
+          .initializeEventMarket(
+            eventId,
+            marketDuration,
+            registeredCreator.publicKey,
+            { ...marketParams, feeCurve: null }
+          )
+          .accountsStrict({
+            market: marketAddress,
+            config: configAddress,
+            marketCreator: registeredCreator.publicKey,
+            creatorRegistry: creatorRegistryAddress,
+            ...getCreatorBondAccounts(
+              marketAddress,
+              registeredCreator.publicKey,
+              collateralMint,
+              program.programId
+            ),
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([registeredCreator])
+          .rpc(),
+        "FeedNotAllowedForCreator"
+      );
+    });
+
+    it("Can not remove a creator with open markets", async () => {
+      await expectProgramError(
+        program.methods
+          .removeCreator()
+          .accountsStrict({
+            creatorRegistry: creatorRegistryAddress,
+            config: configAddress,
+            admin: provider.wallet.publicKey,
+            rentPayer: provider.wallet.publicKey,
+          })
+          .rpc(),
+        "CreatorHasOpenMarkets"
+      );
+
+      await program.methods
+        .updateConfig(configParams)
+        .accountsStrict({
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+    });
+  });
+
+  describe("Collateral Allowlist", () => {
+    it("Adds and removes a collateral mint", async () => {
+      await airdrop(provider.connection, mint_authority.publicKey);
+
+      const mint = await token.createMint(
+        provider.connection,
+        mint_authority,
+        mint_authority.publicKey,
+        null,
+        6
+      );
+      const [collateralConfigAddress] = getCollateralConfigAddress(
+        mint,
+        program.programId
+      );
+
+      //paid by another account, which gets the rent back on removal
+      await program.methods
+        .addCollateralMint(collateralParams)
+        .accountsStrict({
+          collateralConfig: collateralConfigAddress,
+          config: configAddress,
+          mint,
+          admin: provider.wallet.publicKey,
+          payer: mint_authority.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([mint_authority])
+        .rpc()
+        .then(confirmTx);
+
+      const collateralConfig = await program.account.collateralConfig.fetch(
+        collateralConfigAddress
+      );
+      assert.strictEqual(collateralConfig.decimals, 6);
+      assert.strictEqual(
+        collateralConfig.rentPayer.toString(),
+        mint_authority.publicKey.toString()
+      );
+      const rent = await provider.connection.getBalance(
+        collateralConfigAddress
+      );
+      const payerBalanceBefore = await provider.connection.getBalance(
+        mint_authority.publicKey
+      );
+      assert.strictEqual(
+        collateralConfig.minBet.toString(),
+        collateralParams.minBet.toString()
+      );
+
+      await program.methods
+        .removeCollateralMint()
+        .accountsStrict({
+          collateralConfig: collateralConfigAddress,
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+          rentPayer: mint_authority.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      assert.strictEqual(
+        await provider.connection.getBalance(mint_authority.publicKey),
+        payerBalanceBefore + rent
+      );
+
+      assert.isNull(
+        await program.account.collateralConfig.fetchNullable(
+          collateralConfigAddress
+        )
+      );
+    });
+
+    it("Can not add a mint with a minimum above the maximum", async () => {
+      const mint = await token.createMint(
+        provider.connection,
+        mint_authority,
+        mint_authority.publicKey,
+        null,
+        6
+      );
+
+      let should_fail = "This Should Fail";
+      try {
+        await addCollateralMint(program, mint, {
+          minBet: collateralParams.maxBet.addn(1),
+          maxBet: collateralParams.maxBet,
+        });
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "InvalidBetLimits",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+  });
+
+  describe("Pool Initialization", () => {
+    it("Initialize pool mint and token accounts", async () => {
+      await airdrop(provider.connection, marketCreator1.publicKey);
+      await airdrop(provider.connection, mint_authority.publicKey);
+
+      //the pools are backed by the mint the creator bond was posted in
+      const mint = collateralMint;
+
+      const userAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        marketCreator1,
+        mint,
+        marketCreator1.publicKey
+      );
+
+      await token.mintTo(
+        provider.connection,
+        mint_authority,
+        mint,
+        userAta.address,
+        mint_authority,
+        to_mint.toNumber()
+      );
+
+      const [marketAddress, marketBump] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+
+      const [higherPoolAddress, higherPoolBump] = getPoolAddress(
+        HIGHER_POOL_SEED,
+        marketAddress,
+        program.programId
+      );
+
+      const [lowerPoolAddress, lowerPoolBump] = getPoolAddress(
+        LOWER_POOL_SEED,
+        marketAddress,
+        program.programId
+      );
+
+      const [lpMintAddress] = getLpMintAddress(
+        marketAddress,
+        program.programId
+      );
+      const [protocolFeeVaultAddress] = getProtocolFeeVaultAddress(
+        mint,
+        program.programId
+      );
+      const [creatorFeeVaultAddress] = getCreatorFeeVaultAddress(
+        marketAddress,
+        program.programId
+      );
+
+      const [collateralConfigAddress] = getCollateralConfigAddress(
+        mint,
+        program.programId
+      );
+
+      await program.methods
+        .initializePools()
+        .accountsStrict({
+          market: marketAddress,
+          marketCreator: marketCreator1.publicKey,
+          poolTokenMint: mint,
+          collateralConfig: collateralConfigAddress,
+          higherPool: higherPoolAddress,
+          lowerPool: lowerPoolAddress,
+          lpMint: lpMintAddress,
+          config: configAddress,
+          protocolFeeVault: protocolFeeVaultAddress,
+          creatorFeeVault: creatorFeeVaultAddress,
+          userAta: userAta.address,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      await checkMarket(
+        program,
+        marketAddress,
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        marketBump,
+        { initializedPools: {} },
+        higherPoolBump,
+        lowerPoolBump,
+        mint
+      );
+    });
+  });
+
+  describe("Token-2022 Collateral", () => {
+    const TOKEN_2022 = token.TOKEN_2022_PROGRAM_ID;
+
+    // Funds the creator's account of a token-2022 mint and creates a market
+    // bonded in it
+    async function setupToken2022Market(
+      otherTargetPrice: anchor.BN,
+      extension: token.ExtensionType,
+      allowMint = true
+    ) {
+      await airdrop(provider.connection, mint_authority.publicKey);
+
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        otherTargetPrice,
+        marketDuration,
+        program.programId
+      );
+
+      const mint = await createToken2022Mint(
+        provider.connection,
+        mint_authority,
+        extension
+      );
+      if (allowMint) {
+        await addCollateralMint(program, mint, collateralParams);
+      }
+      const userAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        marketCreator1,
+        mint,
+        marketCreator1.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_2022
+      );
+      await token.mintTo(
+        provider.connection,
+        mint_authority,
+        mint,
+        userAta.address,
+        mint_authority,
+        to_mint.toNumber(),
+        [],
+        undefined,
+        TOKEN_2022
+      );
+
+      await program.methods
+        .initializeMarket(
+          otherTargetPrice,
+          feedIdInput,
+          marketDuration,
+          { ...marketParams, feeCurve: null }
+        )
+        .accountsStrict({
+          marketCreator: marketCreator1.publicKey,
+          market: marketAddress,
+          config: configAddress,
+          feedRegistry: feedRegistryAddress,
+          creatorRegistry: null,
+          ...getCreatorBondAccounts(
+            marketAddress,
+            marketCreator1.publicKey,
+            mint,
+            program.programId,
+            TOKEN_2022
+          ),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      return { marketAddress, mint, userAta };
+    }
+
+    function initializePoolsAccounts(
+      marketAddress: PublicKey,
+      mint: PublicKey,
+      userAta: PublicKey
+    ) {
+      return {
+        market: marketAddress,
+        marketCreator: marketCreator1.publicKey,
+        poolTokenMint: mint,
+        collateralConfig: getCollateralConfigAddress(
+          mint,
+          program.programId
+        )[0],
+        higherPool: getPoolAddress(
+          HIGHER_POOL_SEED,
+          marketAddress,
+          program.programId
+        )[0],
+        lowerPool: getPoolAddress(
+          LOWER_POOL_SEED,
+          marketAddress,
+          program.programId
+        )[0],
+        lpMint: getLpMintAddress(marketAddress, program.programId)[0],
+        config: configAddress,
+        protocolFeeVault: getProtocolFeeVaultAddress(
+          mint,
+          program.programId
+        )[0],
+        creatorFeeVault: getCreatorFeeVaultAddress(
+          marketAddress,
+          program.programId
+        )[0],
+        userAta,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_2022,
+      };
+    }
+
+    it("Credits the seed received from a transfer fee mint", async () => {
+      const { marketAddress, mint, userAta } = await setupToken2022Market(
+        new anchor.BN(200),
+        token.ExtensionType.TransferFeeConfig
+      );
+
+      await program.methods
+        .initializePools()
+        .accountsStrict(
+          initializePoolsAccounts(marketAddress, mint, userAta.address)
+        )
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      // The mint withholds 1% of every transfer
+      const market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(
+        market.seedAmount.toString(),
+        ((Number(INITIAL_USDC_AMOUNT) * 99) / 100).toString()
+      );
+      assert.strictEqual(
+        market.creatorBond.toString(),
+        collateralParams.creatorBond.muln(99).divn(100).toString()
+      );
+    });
+
+    it("Can not back a market with a permanent delegate mint", async () => {
+      let should_fail = "This Should Fail";
+      try {
+        await setupToken2022Market(
+          new anchor.BN(210),
+          token.ExtensionType.PermanentDelegate
+        );
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "UnsupportedMintExtension",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+
+    it("Can not back a market with a mint that was not added", async () => {
+      let should_fail = "This Should Fail";
+      try {
+        await setupToken2022Market(
+          new anchor.BN(220),
+          token.ExtensionType.TransferFeeConfig,
+          false
+        );
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "AccountNotInitialized",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+  });
+
+  describe("Native SOL Market", () => {
+    const solTargetPrice = new anchor.BN(230);
+    const solBetAmount = new anchor.BN(2_000_000);
+    //0.001 to 0.005 SOL
+    const betLimits = {
+      minBet: new anchor.BN(1),
+      maxBet: new anchor.BN(5),
+      decimals: 3,
+    };
+    const betDirection = { higher: {} };
+    const [marketAddress] = getMarketAddress(
+      marketCreator1.publicKey,
+      feedIdString,
+      solTargetPrice,
+      marketDuration,
+      program.programId
+    );
+    const [higherPoolAddress] = getPoolAddress(
+      HIGHER_POOL_SEED,
+      marketAddress,
+      program.programId
+    );
+    const [lowerPoolAddress] = getPoolAddress(
+      LOWER_POOL_SEED,
+      marketAddress,
+      program.programId
+    );
+
+    it("Initializes a market backed by wrapped SOL", async () => {
+      await airdrop(provider.connection, marketCreator1.publicKey);
+
+      await addCollateralMint(program, token.NATIVE_MINT, collateralParams);
+      const creatorWsolAta = await token.createWrappedNativeAccount(
+        provider.connection,
+        marketCreator1,
+        marketCreator1.publicKey,
+        LAMPORTS_PER_SOL
+      );
+
+      await program.methods
+        .initializeMarket(
+          solTargetPrice,
+          feedIdInput,
+          marketDuration,
+          { ...marketParams, feeCurve: null, betLimits }
+        )
+        .accountsStrict({
+          marketCreator: marketCreator1.publicKey,
+          market: marketAddress,
+          config: configAddress,
+          feedRegistry: feedRegistryAddress,
+          creatorRegistry: null,
+          ...getCreatorBondAccounts(
+            marketAddress,
+            marketCreator1.publicKey,
+            token.NATIVE_MINT,
+            program.programId
+          ),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      await program.methods
+        .initializePools()
+        .accountsStrict({
+          market: marketAddress,
+          marketCreator: marketCreator1.publicKey,
+          poolTokenMint: token.NATIVE_MINT,
+          collateralConfig: getCollateralConfigAddress(
+            token.NATIVE_MINT,
+            program.programId
+          )[0],
+          higherPool: higherPoolAddress,
+          lowerPool: lowerPoolAddress,
+          lpMint: getLpMintAddress(marketAddress, program.programId)[0],
+          config: configAddress,
+          protocolFeeVault: getProtocolFeeVaultAddress(
+            token.NATIVE_MINT,
+            program.programId
+          )[0],
+          creatorFeeVault: getCreatorFeeVaultAddress(
+            marketAddress,
+            program.programId
+          )[0],
+          userAta: creatorWsolAta,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      const market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(market.mint.toString(), token.NATIVE_MINT.toString());
+      assert.strictEqual(market.minBet.toString(), "1000000");
+      assert.strictEqual(market.maxBet.toString(), "5000000");
+    });
+
+    it("Places a bet in lamports without a token account", async () => {
+      await airdrop(provider.connection, hema.publicKey);
+
+      const [betAddress] = getBetAddress(
+        marketAddress,
+        hema.publicKey,
+        solBetAmount,
+        betDirection,
+        program.programId
+      );
+      const poolBefore = await token.getAccount(
+        provider.connection,
+        higherPoolAddress
+      );
+
+      await program.methods
+        .placeBet(solBetAmount, betDirection)
+        .accountsStrict({
+          bet: betAddress,
+          market: marketAddress,
+          config: configAddress,
+          protocolFeeVault: getProtocolFeeVaultAddress(
+            token.NATIVE_MINT,
+            program.programId
+          )[0],
+          creatorFeeVault: getCreatorFeeVaultAddress(
+            marketAddress,
+            program.programId
+          )[0],
+          referrerStats: null,
+          referralVault: null,
+          user: hema.publicKey,
+          higherPool: higherPoolAddress,
+          lowerPool: lowerPoolAddress,
+          userAta: null,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          mint: token.NATIVE_MINT,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([hema])
+        .rpc()
+        .then(confirmTx);
+
+      const bet = await program.account.bet.fetch(betAddress);
+      const poolAfter = await token.getAccount(
+        provider.connection,
+        higherPoolAddress
+      );
+      assert.isAtLeast(
+        Number(poolAfter.amount - poolBefore.amount),
+        bet.stake.toNumber()
+      );
+    });
+  });
+
+  describe("Place Bet", () => {
+    const betAmount = new anchor.BN(10000);
+    const betDirection = { higher: {} };
+    it("Registers a referrer", async () => {
+      await airdrop(provider.connection, referrer.publicKey);
+
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+      const market = await program.account.market.fetch(marketAddress);
+
+      const [referrerStatsAddress] = getReferrerStatsAddress(
+        referrer.publicKey,
+        market.mint,
+        program.programId
+      );
+      const [referralVaultAddress] = getReferralVaultAddress(
+        market.mint,
+        program.programId
+      );
+
+      await program.methods
+        .registerReferrer()
+        .accountsStrict({
+          referrerStats: referrerStatsAddress,
+          config: configAddress,
+          referralVault: referralVaultAddress,
+          mint: market.mint,
+          referrer: referrer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([referrer])
+        .rpc()
+        .then(confirmTx);
+
+      const referrerStats = await program.account.referrerStats.fetch(
+        referrerStatsAddress
+      );
+      assert.strictEqual(
+        referrerStats.referrer.toString(),
+        referrer.publicKey.toString()
+      );
+      assert.strictEqual(referrerStats.mint.toString(), market.mint.toString());
+    });
+
+    it("Placed Bet", async () => {
+      await airdrop(provider.connection, hema.publicKey);
+      await airdrop(provider.connection, marketCreator1.publicKey);
+
+      const [marketAddress, marketBump] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+
+      const [higherPoolAddress, higherPoolBump] = getPoolAddress(
+        HIGHER_POOL_SEED,
+        marketAddress,
+        program.programId
+      );
+
+      const [lowerPoolAddress, lowerPoolBump] = getPoolAddress(
+        LOWER_POOL_SEED,
+        marketAddress,
+        program.programId
+      );
+
+      const market = await program.account.market.fetch(marketAddress);
+
+      const userAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        hema,
+        market.mint,
+        hema.publicKey
+      );
+
+      await token.mintTo(
+        provider.connection,
+        mint_authority,
+        market.mint,
+        userAta.address,
+        mint_authority,
+        to_mint.toNumber()
+      );
+
+      const [betAddress, betBump] = getBetAddress(
+        marketAddress,
+        hema.publicKey,
+        betAmount,
+        betDirection,
+        program.programId
+      );
+
+      const [protocolFeeVaultAddress] = getProtocolFeeVaultAddress(
+        market.mint,
+        program.programId
+      );
+      const [creatorFeeVaultAddress] = getCreatorFeeVaultAddress(
+        marketAddress,
+        program.programId
+      );
+      const [referrerStatsAddress] = getReferrerStatsAddress(
+        referrer.publicKey,
+        market.mint,
+        program.programId
+      );
+      const [referralVaultAddress] = getReferralVaultAddress(
+        market.mint,
+        program.programId
+      );
+
+      const quote = await program.methods
+        .quoteBet(betAmount, betDirection)
+        .accountsStrict({
+          config: configAddress,
+          market: marketAddress,
+          higherPool: higherPoolAddress,
+          lowerPool: lowerPoolAddress,
+        })
+        .view();
+
+      await program.methods
+        .placeBet(betAmount, betDirection)
+        .accountsStrict({
+          bet: betAddress,
+          market: marketAddress,
+          config: configAddress,
+          protocolFeeVault: protocolFeeVaultAddress,
+          creatorFeeVault: creatorFeeVaultAddress,
+          referrerStats: referrerStatsAddress,
+          referralVault: referralVaultAddress,
+          user: hema.publicKey,
+          higherPool: higherPoolAddress,
+          lowerPool: lowerPoolAddress,
+          userAta: userAta.address,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          mint: market.mint,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([hema])
+        .rpc()
+        .then(confirmTx);
+
+      const expectedProtocolFee = Math.ceil(
+        (betAmount.toNumber() * configParams.protocolFeeBps) / 10000
+      );
+      const expectedCreatorFee = Math.ceil(
+        (betAmount.toNumber() * creatorFeeBps) / 10000
+      );
+      const expectedLpFee = Math.ceil(
+        (betAmount.toNumber() * configParams.lpFeeBps) / 10000
+      );
+      const expectedReferralFee = Math.floor(
+        (expectedProtocolFee * configParams.referralShareBps) / 10000
+      );
+      // the bet lands well before the ramp so the curve charges its minimum
+      const expectedDynamicFee = Math.ceil(
+        (betAmount.toNumber() * feeCurve.feeMinBps) / 10000
+      );
+
+      const bet = await program.account.bet.fetch(betAddress);
+      assert.strictEqual(bet.protocolFee.toNumber(), expectedProtocolFee);
+      assert.strictEqual(bet.creatorFee.toNumber(), expectedCreatorFee);
+      assert.strictEqual(bet.lpFee.toNumber(), expectedLpFee);
+      assert.strictEqual(bet.dynamicFeeBps, feeCurve.feeMinBps);
+      assert.strictEqual(bet.dynamicFee.toNumber(), expectedDynamicFee);
+      assert.strictEqual(
+        bet.stake.toNumber(),
+        betAmount.toNumber() -
+          expectedProtocolFee -
+          expectedCreatorFee -
+          expectedLpFee -
+          expectedDynamicFee
+      );
+      assert.strictEqual(bet.stake.toString(), quote.stake.toString());
+      assert.strictEqual(bet.odds.toString(), quote.odds.toString());
+
+      const protocolFeeVault = await token.getAccount(
+        provider.connection,
+        protocolFeeVaultAddress,
+        "confirmed"
+      );
+      assert.strictEqual(
+        Number(protocolFeeVault.amount),
+        expectedProtocolFee - expectedReferralFee
+      );
+
+      assert.strictEqual(
+        bet.referrer.toString(),
+        referrer.publicKey.toString()
+      );
+      assert.strictEqual(bet.referralFee.toNumber(), expectedReferralFee);
+
+      const referrerStats = await program.account.referrerStats.fetch(
+        referrerStatsAddress
+      );
+      assert.strictEqual(referrerStats.referredBets.toNumber(), 1);
+      assert.strictEqual(
+        referrerStats.referredVolume.toString(),
+        betAmount.toString()
+      );
+      assert.strictEqual(
+        referrerStats.unclaimedRewards.toNumber(),
+        expectedReferralFee
+      );
+
+      const creatorFeeVault = await token.getAccount(
+        provider.connection,
+        creatorFeeVaultAddress,
+        "confirmed"
+      );
+      assert.strictEqual(Number(creatorFeeVault.amount), expectedCreatorFee);
+    });
+
+    it("Can not place a bet below the minimum", async () => {
+      const dustAmount = new anchor.BN(1);
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+      const market = await program.account.market.fetch(marketAddress);
+      const userAta = token.getAssociatedTokenAddressSync(
+        market.mint,
+        hema.publicKey
+      );
+
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .placeBet(dustAmount, betDirection)
+          .accountsStrict({
+            bet: getBetAddress(
+              marketAddress,
+              hema.publicKey,
+              dustAmount,
+              betDirection,
+              program.programId
+            )[0],
+            market: marketAddress,
+            config: configAddress,
+            protocolFeeVault: getProtocolFeeVaultAddress(
+              market.mint,
+              program.programId
+            )[0],
+            creatorFeeVault: getCreatorFeeVaultAddress(
+              marketAddress,
+              program.programId
+            )[0],
+            referrerStats: null,
+            referralVault: null,
+            user: hema.publicKey,
+            higherPool: getPoolAddress(
+              HIGHER_POOL_SEED,
+              marketAddress,
+              program.programId
+            )[0],
+            lowerPool: getPoolAddress(
+              LOWER_POOL_SEED,
+              marketAddress,
+              program.programId
+            )[0],
+            userAta,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            mint: market.mint,
+            tokenProgram: token.TOKEN_PROGRAM_ID,
+          })
+          .signers([hema])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "BetOutOfRange",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+
+    it("Referrer claims referral rewards", async () => {
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+      const market = await program.account.market.fetch(marketAddress);
+      const [referrerStatsAddress] = getReferrerStatsAddress(
+        referrer.publicKey,
+        market.mint,
+        program.programId
+      );
+      const [referralVaultAddress] = getReferralVaultAddress(
+        market.mint,
+        program.programId
+      );
+      const referrerAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        referrer,
+        market.mint,
+        referrer.publicKey
+      );
+      const statsBefore = await program.account.referrerStats.fetch(
+        referrerStatsAddress
+      );
+
+      await program.methods
+        .claimReferralRewards()
+        .accountsStrict({
+          referrerStats: referrerStatsAddress,
+          config: configAddress,
+          referralVault: referralVaultAddress,
+          referrerTokenAccount: referrerAta.address,
+          referrer: referrer.publicKey,
+          mint: market.mint,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([referrer])
+        .rpc()
+        .then(confirmTx);
+
+      const referrerAtaAfter = await token.getAccount(
+        provider.connection,
+        referrerAta.address,
+        "confirmed"
+      );
+      assert.strictEqual(
+        Number(referrerAtaAfter.amount),
+        statsBefore.unclaimedRewards.toNumber()
+      );
+
+      const statsAfter = await program.account.referrerStats.fetch(
+        referrerStatsAddress
+      );
+      assert.strictEqual(statsAfter.unclaimedRewards.toNumber(), 0);
+    });
+
+    it("Creator withdraws creator fees", async () => {
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+      const market = await program.account.market.fetch(marketAddress);
+      const [creatorFeeVaultAddress] = getCreatorFeeVaultAddress(
+        marketAddress,
+        program.programId
+      );
+      const creatorAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        marketCreator1,
+        market.mint,
+        marketCreator1.publicKey
+      );
+      const vaultBefore = await token.getAccount(
+        provider.connection,
+        creatorFeeVaultAddress
+      );
+
+      await program.methods
+        .withdrawCreatorFees()
+        .accountsStrict({
+          market: marketAddress,
+          creatorFeeVault: creatorFeeVaultAddress,
+          creatorAta: creatorAta.address,
+          marketCreator: marketCreator1.publicKey,
+          mint: market.mint,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      const creatorAtaAfter = await token.getAccount(
+        provider.connection,
+        creatorAta.address,
+        "confirmed"
+      );
+      assert.strictEqual(
+        Number(creatorAtaAfter.amount),
+        Number(creatorAta.amount) + Number(vaultBefore.amount)
+      );
+    });
+
+    it("Admin withdraws protocol fees", async () => {
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+      const market = await program.account.market.fetch(marketAddress);
+      const [protocolFeeVaultAddress] = getProtocolFeeVaultAddress(
+        market.mint,
+        program.programId
+      );
+      const treasuryAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        mint_authority,
+        market.mint,
+        provider.wallet.publicKey
+      );
+      const vaultBefore = await token.getAccount(
+        provider.connection,
+        protocolFeeVaultAddress
+      );
+
+      await program.methods
+        .withdrawProtocolFees()
+        .accountsStrict({
+          config: configAddress,
+          protocolFeeVault: protocolFeeVaultAddress,
+          destination: treasuryAta.address,
+          mint: market.mint,
+          admin: provider.wallet.publicKey,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      const treasuryAfter = await token.getAccount(
+        provider.connection,
+        treasuryAta.address,
+        "confirmed"
+      );
+      assert.strictEqual(
+        Number(treasuryAfter.amount),
+        Number(treasuryAta.amount) + Number(vaultBefore.amount)
+      );
+    });
+
+    it("Can not propose a resolution before the market expires", async () => {
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+      const market = await program.account.market.fetch(marketAddress);
+      const proposerAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        mint_authority,
+        market.mint,
+        provider.wallet.publicKey
+      );
+
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .proposeResolution(targetPrice.addn(1))
+          .accountsStrict({
+            market: marketAddress,
+            config: configAddress,
+            resolution: getResolutionAddress(
+              RESOLUTION_SEED,
+              marketAddress,
+              program.programId
+            )[0],
+            resolutionVault: getResolutionAddress(
+              RESOLUTION_VAULT_SEED,
+              marketAddress,
+              program.programId
+            )[0],
+            mint: market.mint,
+            proposerAta: proposerAta.address,
+            proposer: provider.wallet.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: token.TOKEN_PROGRAM_ID,
+          })
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "MarketDurationNotOver",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+  });
+
+  describe("Add Liquidity", () => {
+    const liquidityAmount = new anchor.BN(5000000);
+    const lpBettor = anchor.web3.Keypair.generate();
+    let lpFeed: ManualFeed;
+    let lpMarketAddress: PublicKey;
+
+    function liquidityAccounts(marketAddress: PublicKey) {
+      const [lpMintAddress] = getLpMintAddress(
+        marketAddress,
+        program.programId
+      );
+      return {
+        market: marketAddress,
+        config: configAddress,
+        higherPool: getPoolAddress(
+          HIGHER_POOL_SEED,
+          marketAddress,
+          program.programId
+        )[0],
+        lowerPool: getPoolAddress(
+          LOWER_POOL_SEED,
+          marketAddress,
+          program.programId
+        )[0],
+        lpMint: lpMintAddress,
+        providerAta: token.getAssociatedTokenAddressSync(
+          collateralMint,
+          liquidityProvider.publicKey
+        ),
+        providerLpAta: token.getAssociatedTokenAddressSync(
+          lpMintAddress,
+          liquidityProvider.publicKey
+        ),
+        provider: liquidityProvider.publicKey,
+        mint: collateralMint,
+        tokenProgram: token.TOKEN_PROGRAM_ID,
+      };
+    }
+
+    it("Can not make the first deposit after bets", async () => {
+      await airdrop(provider.connection, liquidityProvider.publicKey);
+
+      const providerAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        liquidityProvider,
+        collateralMint,
+        liquidityProvider.publicKey
+      );
+      await token.mintTo(
+        provider.connection,
+        mint_authority,
+        collateralMint,
+        providerAta.address,
+        mint_authority,
+        to_mint.toNumber()
+      );
+
+      //the market of the Place Bet tests already holds their stakes
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+      await expectProgramError(
+        program.methods
+          .addLiquidity(liquidityAmount)
+          .accountsStrict({
+            ...liquidityAccounts(marketAddress),
+            systemProgram: anchor.web3.SystemProgram.programId,
+            associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+          })
+          .signers([liquidityProvider])
+          .rpc(),
+        "FirstDepositAfterBets"
+      );
+    });
+
+    it("Deposits balanced liquidity and mints LP tokens", async () => {
+      lpFeed = await registerManualFeed("LPS/USD", -8);
+      lpMarketAddress = await createShortMarket(lpFeed, 140);
+      const accounts = liquidityAccounts(lpMarketAddress);
+
+      const higherPoolBefore = await token.getAccount(
+        provider.connection,
+        accounts.higherPool
+      );
+      const lowerPoolBefore = await token.getAccount(
+        provider.connection,
+        accounts.lowerPool
+      );
+
+      await program.methods
+        .addLiquidity(liquidityAmount)
+        .accountsStrict({
+          ...accounts,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([liquidityProvider])
+        .rpc()
+        .then(confirmTx);
+
+      const lpAccount = await token.getAccount(
+        provider.connection,
+        accounts.providerLpAta,
+        "confirmed"
+      );
+      assert.strictEqual(
+        lpAccount.amount.toString(),
+        liquidityAmount.toString()
+      );
+
+      const higherPoolAfter = await token.getAccount(
+        provider.connection,
+        accounts.higherPool,
+        "confirmed"
+      );
+      const lowerPoolAfter = await token.getAccount(
+        provider.connection,
+        accounts.lowerPool,
+        "confirmed"
+      );
+      assert.strictEqual(
+        Number(higherPoolAfter.amount),
+        Number(higherPoolBefore.amount) + liquidityAmount.toNumber()
+      );
+      assert.strictEqual(
+        Number(lowerPoolAfter.amount),
+        Number(lowerPoolBefore.amount) + liquidityAmount.toNumber()
+      );
+    });
+
+    it("Prices a later deposit at the surplus of the pools", async () => {
+      await placeShortBet(
+        lpMarketAddress,
+        lpBettor,
+        new anchor.BN(2_000_000),
+        { higher: {} }
+      );
+
+      const accounts = liquidityAccounts(lpMarketAddress);
+      const market = await program.account.market.fetch(lpMarketAddress);
+
+      //the larger payout and both seeds are set aside from the pools
+      const higherPool = await token.getAccount(
+        provider.connection,
+        accounts.higherPool
+      );
+      const lowerPool = await token.getAccount(
+        provider.connection,
+        accounts.lowerPool
+      );
+      const lpMint = await token.getMint(provider.connection, accounts.lpMint);
+      const reserved = market.seedAmount
+        .muln(2)
+        .add(anchor.BN.max(market.higherLiability, market.lowerLiability));
+      const surplus = new anchor.BN(higherPool.amount.toString())
+        .add(new anchor.BN(lowerPool.amount.toString()))
+        .sub(reserved);
+      const expectedLp = liquidityAmount
+        .muln(2)
+        .mul(new anchor.BN(lpMint.supply.toString()))
+        .div(surplus);
+      const lpBefore = await token.getAccount(
+        provider.connection,
+        accounts.providerLpAta
+      );
+
+      await program.methods
+        .addLiquidity(liquidityAmount)
+        .accountsStrict({
+          ...accounts,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([liquidityProvider])
+        .rpc()
+        .then(confirmTx);
+
+      const lpAfter = await token.getAccount(
+        provider.connection,
+        accounts.providerLpAta,
+        "confirmed"
+      );
+      assert.strictEqual(
+        (lpAfter.amount - lpBefore.amount).toString(),
+        expectedLp.toString()
+      );
+    });
+
+    it("Withdraws a share of the pools after settlement", async () => {
+      await waitForExpiry(lpMarketAddress);
+      await publishPrice(lpFeed, new anchor.BN(15_000_000_000));
+      await settleShortMarket(lpMarketAddress, lpFeed.oracleAddress);
+
+      const accounts = liquidityAccounts(lpMarketAddress);
+      const market = await program.account.market.fetch(lpMarketAddress);
+      assert.strictEqual(market.finalPrice.toString(), "150");
+
+      //the seeds and the payout owed to the winning higher bet stay behind
+      const higherPool = await token.getAccount(
+        provider.connection,
+        accounts.higherPool
+      );
+      const lowerPool = await token.getAccount(
+        provider.connection,
+        accounts.lowerPool
+      );
+      const lpMint = await token.getMint(provider.connection, accounts.lpMint);
+      const lpAccount = await token.getAccount(
+        provider.connection,
+        accounts.providerLpAta
+      );
+      const lpAmount = new anchor.BN(lpAccount.amount.toString());
+      const lpSupply = new anchor.BN(lpMint.supply.toString());
+      const higherShare = new anchor.BN(higherPool.amount.toString())
+        .sub(market.seedAmount)
+        .sub(market.higherLiability)
+        .mul(lpAmount)
+        .div(lpSupply);
+      const lowerShare = new anchor.BN(lowerPool.amount.toString())
+        .sub(market.seedAmount)
+        .mul(lpAmount)
+        .div(lpSupply);
+      const providerBefore = await token.getAccount(
+        provider.connection,
+        accounts.providerAta
+      );
+
+      await program.methods
+        .removeLiquidity(lpAmount)
+        .accountsStrict(accounts)
+        .signers([liquidityProvider])
+        .rpc()
+        .then(confirmTx);
+
+      const providerAfter = await token.getAccount(
+        provider.connection,
+        accounts.providerAta,
+        "confirmed"
+      );
+      assert.strictEqual(
+        (providerAfter.amount - providerBefore.amount).toString(),
+        higherShare.add(lowerShare).toString()
+      );
+      const lpMintAfter = await token.getMint(
+        provider.connection,
+        accounts.lpMint,
+        "confirmed"
+      );
+      assert.strictEqual(lpMintAfter.supply.toString(), "0");
+    });
+
+    it("Can not deposit while betting is paused", async () => {
+      const [marketAddress] = getMarketAddress(
+        marketCreator1.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+      const [lpMintAddress] = getLpMintAddress(
+        marketAddress,
+        program.programId
+      );
+      const market = await program.account.market.fetch(marketAddress);
+      const noPause = { betting: false, claiming: false, creation: false };
+
+      await program.methods
+        .setMarketPause({ ...noPause, betting: true })
+        .accountsStrict({
+          market: marketAddress,
+          config: configAddress,
+          authority: guardian.publicKey,
+        })
+        .signers([guardian])
+        .rpc()
+        .then(confirmTx);
+
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .addLiquidity(liquidityAmount)
+          .accountsStrict({
+            market: marketAddress,
+            config: configAddress,
+            higherPool: getPoolAddress(
+              HIGHER_POOL_SEED,
+              marketAddress,
+              program.programId
+            )[0],
+            lowerPool: getPoolAddress(
+              LOWER_POOL_SEED,
+              marketAddress,
+              program.programId
+            )[0],
+            lpMint: lpMintAddress,
+            providerAta: token.getAssociatedTokenAddressSync(
+              market.mint,
+              liquidityProvider.publicKey
+            ),
+            providerLpAta: token.getAssociatedTokenAddressSync(
+              lpMintAddress,
+              liquidityProvider.publicKey
+            ),
+            provider: liquidityProvider.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            mint: market.mint,
+            tokenProgram: token.TOKEN_PROGRAM_ID,
+            associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+          })
+          .signers([liquidityProvider])
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "ActionPaused",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+
+      await program.methods
+        .setMarketPause(noPause)
+        .accountsStrict({
+          market: marketAddress,
+          config: configAddress,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+    });
+  });
+
+  describe("Market Settlement", () => {
+    //preloaded from tests/fixtures/partial-price-update.json
+    const partialPriceUpdate = new PublicKey(
+      "98GtnCGSsjsHKwNEuCG9FJWD4DfoDMSpLoUg3yhRyYBX"
+    );
+
+    it("Can not settle on a price with a wide confidence", async () => {
+      const feed = await registerManualFeed("CNF/USD", -8);
+      const marketAddress = await createShortMarket(feed, 140);
+      await waitForExpiry(marketAddress);
+
+      //3% of the price against a limit of 2%
+      await publishPrice(
+        feed,
+        new anchor.BN(15_000_000_000),
+        new anchor.BN(450_000_000)
+      );
+      await expectProgramError(
+        settleShortMarket(marketAddress, feed.oracleAddress),
+        "PriceConfidenceTooWide"
+      );
+
+      //settlement can be retried once a confident price is published
+      await publishPrice(
+        feed,
+        new anchor.BN(15_000_000_000),
+        new anchor.BN(150_000_000)
+      );
+      await settleShortMarket(marketAddress, feed.oracleAddress);
+
+      const market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(market.finalPrice.toString(), "150");
+    });
+
+    it("Can not settle on a price published too late", async () => {
+      const feed = await registerManualFeed("LTE/USD", -8, {
+        maxPriceAgeSecs: new anchor.BN(1),
+      });
+      const marketAddress = await createShortMarket(feed, 140);
+      await waitForExpiry(marketAddress);
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      await publishPrice(feed, new anchor.BN(15_000_000_000));
+      await expectProgramError(
+        settleShortMarket(marketAddress, feed.oracleAddress),
+        "PriceOutsideSettlementWindow"
+      );
+    });
+
+    it("Can not settle on a partially verified pyth update", async () => {
+      const marketAddress = await createShortMarket(
+        { feedIdString, feedRegistry: feedRegistryAddress },
+        145
+      );
+      await waitForExpiry(marketAddress);
+
+      //the market accepts fully verified updates only
+      await expectProgramError(
+        settleShortMarket(marketAddress, partialPriceUpdate),
+        "InsufficientPriceVerification"
+      );
+    });
+
+    it("Settles on the valid readings of several feeds", async () => {
+      const feed = await registerManualFeed("MF1/USD", -8);
+      //would pass the limit of the market feed but not its own
+      const tightFeed = await registerManualFeed("MF2/USD", -8, {
+        maxConfBps: 50,
+      });
+      //never publishes a price
+      const silentFeed = await registerManualFeed("MF3/USD", -8);
+      const marketAddress = await createShortMarket(
+        feed,
+        140,
+        { minAgreeingReadings: 1, agreementToleranceBps: 100 },
+        [tightFeed, silentFeed]
+      );
+      await waitForExpiry(marketAddress);
+
+      await publishPrice(feed, new anchor.BN(15_000_000_000));
+      await publishPrice(
+        tightFeed,
+        new anchor.BN(14_900_000_000),
+        new anchor.BN(149_000_000)
+      );
+
+      //an account of another feed aborts settlement instead of being skipped
+      await expectProgramError(
+        settleShortMarket(marketAddress, feed.oracleAddress, [
+          tightFeed.oracleAddress,
+          feed.oracleAddress,
+        ]),
+        "OracleFeedMismatch"
+      );
+
+      await settleShortMarket(marketAddress, feed.oracleAddress, [
+        tightFeed.oracleAddress,
+        silentFeed.oracleAddress,
+      ]);
+
+      const market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(market.extraFeeds[0].maxConfBps, 50);
+      assert.strictEqual(market.finalPrice.toString(), "150");
+      assert.strictEqual(market.settlementReadings.length, 1);
+      assert.deepEqual(
+        market.settlementReadings[0].feedId,
+        Array.from(feed.oracleAddress.toBuffer())
+      );
+    });
+
+    it("Pushes the settlement deadline back by paused slots", async () => {
+      const feed = await registerManualFeed("PSD/USD", -8);
+      const marketAddress = await createShortMarket(feed, 140, {
+        settlementWindow: new anchor.BN(10),
+      });
+      const noPause = { betting: false, claiming: false, creation: false };
+
+      await program.methods
+        .setMarketPause({ ...noPause, claiming: true })
+        .accountsStrict({
+          market: marketAddress,
+          config: configAddress,
+          authority: guardian.publicKey,
+        })
+        .signers([guardian])
+        .rpc()
+        .then(confirmTx);
+
+      //the unpaused deadline passes while the market is paused
+      const market = await program.account.market.fetch(marketAddress);
+      const deadline = market.startTime
+        .add(market.marketDuration)
+        .add(market.settlementWindow);
+      await waitForSlot(deadline.addn(10).toNumber());
+
+      await program.methods
+        .setMarketPause(noPause)
+        .accountsStrict({
+          market: marketAddress,
+          config: configAddress,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      const unpaused = await program.account.market.fetch(marketAddress);
+      assert.isNull(unpaused.claimingPaused.since);
+      assert.isAbove(unpaused.claimingPaused.total.toNumber(), 10);
+
+      await expectProgramError(
+        program.methods
+          .voidMarket()
+          .accountsStrict({ market: marketAddress, config: configAddress })
+          .rpc(),
+        "SettlementWindowNotOver"
+      );
+    });
+
+    it("Settles and claims on an exponent -5 oracle", async () => {
+      const feed = await registerManualFeed("EXP/USD", -5);
+      const marketAddress = await createShortMarket(feed, 3450);
+      const bettor = anchor.web3.Keypair.generate();
+      const betAddress = await placeShortBet(
+        marketAddress,
+        bettor,
+        new anchor.BN(1_000_000),
+        { higher: {} }
+      );
+      await waitForExpiry(marketAddress);
+
+      //3450.12345 rounds down to the target, which it was strictly above
+      await publishPrice(
+        feed,
+        new anchor.BN(345_012_345),
+        new anchor.BN(10_000)
+      );
+
+      const bet = await program.account.bet.fetch(betAddress);
+      const payout = bet.stake.mul(bet.odds).divn(1_000_000);
+      const bettorAta = token.getAssociatedTokenAddressSync(
+        collateralMint,
+        bettor.publicKey
+      );
+      const ataBefore = await token.getAccount(provider.connection, bettorAta);
+
+      await claimShortBet(
+        marketAddress,
+        bettor,
+        betAddress,
+        feed.oracleAddress
+      );
+
+      const ataAfter = await token.getAccount(
+        provider.connection,
+        bettorAta,
+        "confirmed"
+      );
+      assert.strictEqual(
+        (ataAfter.amount - ataBefore.amount).toString(),
+        payout.toString()
+      );
+
+      //the confidence is kept in the exponent it was published in
+      const market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(market.finalPrice.toString(), "3450");
+      assert.isTrue(market.finalPriceTruncated);
+      assert.strictEqual(market.finalConf.toString(), "10000");
+      assert.strictEqual(market.finalConfExponent, -5);
+    });
+  });
+
+  describe("Optimistic Resolution", () => {
+    const bond = new anchor.BN(200_000);
+    const optimisticResolution = { bond, challengeWindow: new anchor.BN(30) };
+    const proposer = anchor.web3.Keypair.generate();
+    const disputer = anchor.web3.Keypair.generate();
+    let feed: ManualFeed;
+
+    // Funds `user` with collateralMint for a few bonds
+    async function fundBonds(user: anchor.web3.Keypair) {
+      await airdrop(provider.connection, user.publicKey);
+      const userAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        user,
+        collateralMint,
+        user.publicKey
+      );
+      await token.mintTo(
+        provider.connection,
+        mint_authority,
+        collateralMint,
+        userAta.address,
+        mint_authority,
+        bond.muln(10).toNumber()
+      );
+      return userAta.address;
+    }
+
+    async function balance(ata: PublicKey) {
+      const account = await token.getAccount(
+        provider.connection,
+        ata,
+        "confirmed"
+      );
+      return new anchor.BN(account.amount.toString());
+    }
+
+    function resolutionAccounts(marketAddress: PublicKey) {
+      return {
+        market: marketAddress,
+        config: configAddress,
+        resolution: getResolutionAddress(
+          RESOLUTION_SEED,
+          marketAddress,
+          program.programId
+        )[0],
+        resolutionVault: getResolutionAddress(
+          RESOLUTION_VAULT_SEED,
+          marketAddress,
+          program.programId
+        )[0],
+        mint: collateralMint,
+        tokenProgram: token.TOKEN_PROGRAM_ID,
+      };
+    }
+
+    // Creates an expired optimistic market on `price` and proposes 150
+    async function proposeOnNewMarket(price: number) {
+      const marketAddress = await createShortMarket(feed, price, {
+        optimisticResolution,
+      });
+      await waitForExpiry(marketAddress);
+
+      await program.methods
+        .proposeResolution(new anchor.BN(150))
+        .accountsStrict({
+          ...resolutionAccounts(marketAddress),
+          proposerAta: token.getAssociatedTokenAddressSync(
+            collateralMint,
+            proposer.publicKey
+          ),
+          proposer: proposer.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([proposer])
+        .rpc()
+        .then(confirmTx);
+
+      return marketAddress;
+    }
+
+    async function dispute(marketAddress: PublicKey) {
+      const { config, ...accounts } = resolutionAccounts(marketAddress);
+      await program.methods
+        .disputeResolution()
+        .accountsStrict({
+          ...accounts,
+          disputerAta: token.getAssociatedTokenAddressSync(
+            collateralMint,
+            disputer.publicKey
+          ),
+          disputer: disputer.publicKey,
+        })
+        .signers([disputer])
+        .rpc()
+        .then(confirmTx);
+    }
+
+    async function arbitrate(
+      marketAddress: PublicKey,
+      price: number,
+      recipient: anchor.web3.Keypair
+    ) {
+      await program.methods
+        .arbitrateResolution(new anchor.BN(price))
+        .accountsStrict({
+          ...resolutionAccounts(marketAddress),
+          bondRecipientAta: token.getAssociatedTokenAddressSync(
+            collateralMint,
+            recipient.publicKey
+          ),
+          proposer: proposer.publicKey,
+          arbiter: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+    }
+
+    before(async () => {
+      feed = await registerManualFeed("OPT/USD", -8);
+    });
+
+    it("Settles on an undisputed proposal and returns its bond", async () => {
+      const proposerAta = await fundBonds(proposer);
+      const proposerBefore = await balance(proposerAta);
+
+      const marketAddress = await proposeOnNewMarket(140);
+      const accounts = resolutionAccounts(marketAddress);
+      const resolution = await program.account.resolution.fetch(
+        accounts.resolution
+      );
+      assert.strictEqual(
+        (await balance(proposerAta)).toString(),
+        proposerBefore.sub(bond).toString()
+      );
+
+      await expectProgramError(
+        program.methods
+          .finalizeResolution()
+          .accountsStrict({
+            ...accounts,
+            proposerAta,
+            proposer: proposer.publicKey,
+          })
+          .rpc(),
+        "ChallengeWindowNotOver"
+      );
+
+      const challengeEnd = resolution.proposedAt.add(
+        optimisticResolution.challengeWindow
+      );
+      await waitForSlot(challengeEnd.toNumber());
+      await program.methods
+        .finalizeResolution()
+        .accountsStrict({
+          ...accounts,
+          proposerAta,
+          proposer: proposer.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
 
-  describe("Market Initialization", () => {
-    it("Initializes a market", async () => {
-      await airdrop(provider.connection, marketCreator1.publicKey);
+      const market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(market.finalPrice.toString(), "150");
+      assert.isFalse(market.resolutionPending);
+      assert.strictEqual(
+        (await balance(proposerAta)).toString(),
+        proposerBefore.toString()
+      );
+      //the resolution is closed to the proposer with its vault
+      assert.isNull(
+        await provider.connection.getAccountInfo(accounts.resolution)
+      );
+      assert.isNull(
+        await provider.connection.getAccountInfo(accounts.resolutionVault)
+      );
+    });
 
-      const [marketAddress, marketBump] = getMarketAddress(
-        marketCreator1.publicKey,
-        feedIdString,
-        targetPrice,
-        marketDuration,
-        program.programId
+    it("Pays both bonds to a proposer the arbiter agrees with", async () => {
+      const proposerAta = await fundBonds(proposer);
+      const disputerAta = await fundBonds(disputer);
+      const proposerBefore = await balance(proposerAta);
+      const disputerBefore = await balance(disputerAta);
+
+      const marketAddress = await proposeOnNewMarket(141);
+      await dispute(marketAddress);
+
+      //the bond of the losing side can not be claimed
+      await expectProgramError(
+        arbitrate(marketAddress, 150, disputer),
+        "UnauthorizedUser"
+      );
+      await arbitrate(marketAddress, 150, proposer);
+
+      const market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(market.finalPrice.toString(), "150");
+      assert.strictEqual(
+        (await balance(proposerAta)).toString(),
+        proposerBefore.add(bond).toString()
+      );
+      assert.strictEqual(
+        (await balance(disputerAta)).toString(),
+        disputerBefore.sub(bond).toString()
+      );
+      assert.isNull(
+        await provider.connection.getAccountInfo(
+          resolutionAccounts(marketAddress).resolution
+        )
+      );
+    });
+
+    it("Pays both bonds to a disputer the arbiter agrees with", async () => {
+      const proposerAta = await fundBonds(proposer);
+      const disputerAta = await fundBonds(disputer);
+      const proposerBefore = await balance(proposerAta);
+      const disputerBefore = await balance(disputerAta);
+
+      const marketAddress = await proposeOnNewMarket(145);
+      await dispute(marketAddress);
+      await arbitrate(marketAddress, 130, disputer);
+
+      const market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(market.finalPrice.toString(), "130");
+      assert.strictEqual(
+        (await balance(proposerAta)).toString(),
+        proposerBefore.sub(bond).toString()
+      );
+      assert.strictEqual(
+        (await balance(disputerAta)).toString(),
+        disputerBefore.add(bond).toString()
+      );
+      assert.isNull(
+        await provider.connection.getAccountInfo(
+          resolutionAccounts(marketAddress).resolution
+        )
       );
+    });
+
+    it("Returns both bonds of a dispute left undecided", async () => {
+      const proposerAta = await fundBonds(proposer);
+      const disputerAta = await fundBonds(disputer);
+      const proposerBefore = await balance(proposerAta);
+      const disputerBefore = await balance(disputerAta);
 
       await program.methods
-        .initializeMarket(targetPrice, feedIdString, marketDuration)
+        .updateConfig({ ...configParams, arbitrationWindow: new anchor.BN(5) })
         .accountsStrict({
-          market: marketAddress,
-          marketCreator: marketCreator1.publicKey,
-          systemProgram: anchor.web3.SystemProgram.programId,
+          config: configAddress,
+          admin: provider.wallet.publicKey,
         })
-        .signers([marketCreator1])
         .rpc()
         .then(confirmTx);
 
-      await checkMarket(
-        program,
-        marketAddress,
-        marketCreator1.publicKey,
-        feedIdString,
-        targetPrice,
-        marketDuration,
-        marketBump,
-        { initializedMarket: {} }
+      const marketAddress = await proposeOnNewMarket(135);
+      await dispute(marketAddress);
+      const accounts = {
+        ...resolutionAccounts(marketAddress),
+        proposerAta,
+        disputerAta,
+        proposer: proposer.publicKey,
+      };
+
+      await expectProgramError(
+        program.methods.expireResolution().accountsStrict(accounts).rpc(),
+        "ArbitrationWindowNotOver"
       );
-    });
-    it("Can not initialize with invalid FeedId", async () => {
-      await airdrop(provider.connection, marketCreator1.publicKey);
 
-      let should_fail = "This Should Fail";
-      try {
-        const [marketAddress, marketBump] = getMarketAddress(
-          marketCreator1.publicKey,
-          feedIdString2,
-          targetPrice,
-          marketDuration,
-          program.programId
-        );
+      const resolution = await program.account.resolution.fetch(
+        accounts.resolution
+      );
+      await waitForSlot(
+        resolution.proposedAt
+          .add(optimisticResolution.challengeWindow)
+          .addn(5)
+          .toNumber()
+      );
+      await program.methods
+        .expireResolution()
+        .accountsStrict(accounts)
+        .rpc()
+        .then(confirmTx);
 
-        await program.methods
-          .initializeMarket(targetPrice, feedIdString2, marketDuration)
-          .accountsStrict({
-            marketCreator: marketCreator1.publicKey,
-            market: marketAddress,
-            systemProgram: anchor.web3.SystemProgram.programId,
-          })
-          .signers([marketCreator1])
-          .rpc()
-          .then(confirmTx);
-      } catch (e) {
-        const anchorErr = anchor.AnchorError.parse(e.logs);
-        assert.strictEqual(
-          anchorErr.error.errorCode.code,
-          "IncorrectFeedIDLength",
-          "Unexpected Error Code"
-        );
-        should_fail = "Failed";
-      }
-      assert.strictEqual(should_fail, "Failed");
+      await program.methods
+        .updateConfig(configParams)
+        .accountsStrict({
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      //the market is back to waiting for a price, or for void_market
+      const market = await program.account.market.fetch(marketAddress);
+      assert.isFalse(market.resolutionPending);
+      assert.isNull(market.finalPrice);
+      assert.strictEqual(
+        (await balance(proposerAta)).toString(),
+        proposerBefore.toString()
+      );
+      assert.strictEqual(
+        (await balance(disputerAta)).toString(),
+        disputerBefore.toString()
+      );
+      assert.isNull(
+        await provider.connection.getAccountInfo(accounts.resolution)
+      );
     });
   });
-  describe("Pool Initialization", () => {
-    it("Initialize pool mint and token accounts", async () => {
-      await airdrop(provider.connection, marketCreator1.publicKey);
+
+  describe("Cancel Market", () => {
+    let mint: PublicKey;
+
+    it("Initializes market again", async () => {
+      await airdrop(provider.connection, hema.publicKey);
       await airdrop(provider.connection, mint_authority.publicKey);
 
-      const mint = await token.createMint(
+      mint = await token.createMint(
         provider.connection,
         mint_authority,
         mint_authority.publicKey,
         null,
-        6
+        9
       );
+      await addCollateralMint(program, mint, collateralParams);
 
       const userAta = await token.getOrCreateAssociatedTokenAccount(
         provider.connection,
-        marketCreator1,
+        hema,
         mint,
-        marketCreator1.publicKey
+        hema.publicKey
       );
 
       await token.mintTo(
@@ -135,7 +3806,62 @@ describe("prediction_market", () => {
       );
 
       const [marketAddress, marketBump] = getMarketAddress(
-        marketCreator1.publicKey,
+        hema.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+
+      await program.methods
+        .initializeMarket(
+          targetPrice,
+          feedIdInput,
+          marketDuration,
+          { ...marketParams, feeCurve: null }
+        )
+        .accountsStrict({
+          market: marketAddress,
+          marketCreator: hema.publicKey,
+          config: configAddress,
+          feedRegistry: feedRegistryAddress,
+          creatorRegistry: null,
+          ...getCreatorBondAccounts(
+            marketAddress,
+            hema.publicKey,
+            mint,
+            program.programId
+          ),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([hema])
+        .rpc()
+        .then(confirmTx);
+
+      await checkMarket(
+        program,
+        marketAddress,
+        hema.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        marketBump,
+        { initializedMarket: {} }
+      );
+    });
+
+    it("Initialize pool again", async () => {
+      await airdrop(provider.connection, hema.publicKey);
+
+      const userAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        hema,
+        mint,
+        hema.publicKey
+      );
+
+      const [marketAddress, marketBump] = getMarketAddress(
+        hema.publicKey,
         feedIdString,
         targetPrice,
         marketDuration,
@@ -154,26 +3880,49 @@ describe("prediction_market", () => {
         program.programId
       );
 
+      const [lpMintAddress] = getLpMintAddress(
+        marketAddress,
+        program.programId
+      );
+      const [protocolFeeVaultAddress] = getProtocolFeeVaultAddress(
+        mint,
+        program.programId
+      );
+      const [creatorFeeVaultAddress] = getCreatorFeeVaultAddress(
+        marketAddress,
+        program.programId
+      );
+
+      const [collateralConfigAddress] = getCollateralConfigAddress(
+        mint,
+        program.programId
+      );
+
       await program.methods
         .initializePools()
         .accountsStrict({
           market: marketAddress,
-          marketCreator: marketCreator1.publicKey,
+          marketCreator: hema.publicKey,
           poolTokenMint: mint,
+          collateralConfig: collateralConfigAddress,
           higherPool: higherPoolAddress,
           lowerPool: lowerPoolAddress,
+          lpMint: lpMintAddress,
+          config: configAddress,
+          protocolFeeVault: protocolFeeVaultAddress,
+          creatorFeeVault: creatorFeeVaultAddress,
           userAta: userAta.address,
           systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: token.TOKEN_PROGRAM_ID,
         })
-        .signers([marketCreator1])
+        .signers([hema])
         .rpc()
         .then(confirmTx);
 
       await checkMarket(
         program,
         marketAddress,
-        marketCreator1.publicKey,
+        hema.publicKey,
         feedIdString,
         targetPrice,
         marketDuration,
@@ -184,82 +3933,124 @@ describe("prediction_market", () => {
         mint
       );
     });
-  });
-
-  describe("Place Bet", () => {
-    const betAmount = new anchor.BN(10000);
-    const betDirection = { higher: {} };
-    it("Placed Bet", async () => {
+    it("Market Canceled", async () => {
       await airdrop(provider.connection, hema.publicKey);
-      await airdrop(provider.connection, marketCreator1.publicKey);
 
       const [marketAddress, marketBump] = getMarketAddress(
-        marketCreator1.publicKey,
+        hema.publicKey,
         feedIdString,
         targetPrice,
         marketDuration,
         program.programId
       );
+      const market = await program.account.market.fetch(marketAddress);
 
       const [higherPoolAddress, higherPoolBump] = getPoolAddress(
         HIGHER_POOL_SEED,
         marketAddress,
         program.programId
       );
-
       const [lowerPoolAddress, lowerPoolBump] = getPoolAddress(
         LOWER_POOL_SEED,
         marketAddress,
         program.programId
       );
-
-      const market = await program.account.market.fetch(marketAddress);
-
-      const userAta = await token.getOrCreateAssociatedTokenAccount(
+      const creatorAta = await token.getOrCreateAssociatedTokenAccount(
         provider.connection,
         hema,
         market.mint,
         hema.publicKey
       );
 
-      await token.mintTo(
-        provider.connection,
-        mint_authority,
-        market.mint,
-        userAta.address,
-        mint_authority,
-        to_mint.toNumber()
-      );
-
-      const [betAddress, betBump] = getBetAddress(
+      const [creatorFeeVaultAddress] = getCreatorFeeVaultAddress(
         marketAddress,
-        hema.publicKey,
-        betAmount,
-        betDirection,
         program.programId
       );
+      const creatorBalanceBefore = Number(creatorAta.amount);
 
       await program.methods
-        .placeBet(betAmount, betDirection)
+        .cancelMarket()
         .accountsStrict({
-          bet: betAddress,
           market: marketAddress,
-          user: hema.publicKey,
+          marketCreator: hema.publicKey,
           higherPool: higherPoolAddress,
           lowerPool: lowerPoolAddress,
-          userAta: userAta.address,
+          creatorAta: creatorAta.address,
+          creatorFeeVault: creatorFeeVaultAddress,
+          creatorRegistry: null,
+          bondVault: getBondVaultAddress(marketAddress, program.programId)[0],
           systemProgram: anchor.web3.SystemProgram.programId,
+          mint: market.mint,
           tokenProgram: token.TOKEN_PROGRAM_ID,
         })
         .signers([hema])
         .rpc()
         .then(confirmTx);
+
+      //the seeds and the bond go back to the creator
+      const creatorAtaAfter = await token.getAccount(
+        provider.connection,
+        creatorAta.address,
+        "confirmed"
+      );
+      assert.strictEqual(
+        Number(creatorAtaAfter.amount),
+        creatorBalanceBefore +
+          market.seedAmount.toNumber() * 2 +
+          market.creatorBond.toNumber()
+      );
+      const bondVault = await provider.connection.getAccountInfo(
+        getBondVaultAddress(marketAddress, program.programId)[0]
+      );
+      assert.isNull(bondVault);
+
+      const cancelledMarket = await program.account.market.fetchNullable(
+        marketAddress
+      );
+      assert.isNull(cancelledMarket);
+
+      const higherPool = await program.account.market.fetchNullable(
+        higherPoolAddress
+      );
+      assert.isNull(higherPool);
+
+      const lowerPool = await program.account.market.fetchNullable(
+        lowerPoolAddress
+      );
+      assert.isNull(lowerPool);
     });
   });
+  describe("Finalize Market", () => {
+    let mint: PublicKey;
+
+    it("Initializes market again", async () => {
+      await airdrop(provider.connection, hema.publicKey);
+      await airdrop(provider.connection, mint_authority.publicKey);
+
+      mint = await token.createMint(
+        provider.connection,
+        mint_authority,
+        mint_authority.publicKey,
+        null,
+        9
+      );
+      await addCollateralMint(program, mint, collateralParams);
+
+      const userAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        hema,
+        mint,
+        hema.publicKey
+      );
 
-  describe("Cancel Market", () => {
-    it("Initializes market again", async () => {
-      await airdrop(provider.connection, hema.publicKey);
+      await token.mintTo(
+        provider.connection,
+        mint_authority,
+        mint,
+        userAta.address,
+        mint_authority,
+        to_mint.toNumber()
+      );
 
       const [marketAddress, marketBump] = getMarketAddress(
         hema.publicKey,
@@ -270,10 +4061,24 @@ describe("prediction_market", () => {
       );
 
       await program.methods
-        .initializeMarket(targetPrice, feedIdString, marketDuration)
+        .initializeMarket(
+          targetPrice,
+          feedIdInput,
+          marketDuration,
+          { ...marketParams, feeCurve: null }
+        )
         .accountsStrict({
           market: marketAddress,
           marketCreator: hema.publicKey,
+          config: configAddress,
+          feedRegistry: feedRegistryAddress,
+          creatorRegistry: null,
+          ...getCreatorBondAccounts(
+            marketAddress,
+            hema.publicKey,
+            mint,
+            program.programId
+          ),
           systemProgram: anchor.web3.SystemProgram.programId,
         })
         .signers([hema])
@@ -294,15 +4099,6 @@ describe("prediction_market", () => {
 
     it("Initialize pool again", async () => {
       await airdrop(provider.connection, hema.publicKey);
-      await airdrop(provider.connection, mint_authority.publicKey);
-
-      const mint = await token.createMint(
-        provider.connection,
-        mint_authority,
-        mint_authority.publicKey,
-        null,
-        9
-      );
 
       const userAta = await token.getOrCreateAssociatedTokenAccount(
         provider.connection,
@@ -311,15 +4107,6 @@ describe("prediction_market", () => {
         hema.publicKey
       );
 
-      await token.mintTo(
-        provider.connection,
-        mint_authority,
-        mint,
-        userAta.address,
-        mint_authority,
-        to_mint.toNumber()
-      );
-
       const [marketAddress, marketBump] = getMarketAddress(
         hema.publicKey,
         feedIdString,
@@ -340,14 +4127,37 @@ describe("prediction_market", () => {
         program.programId
       );
 
+      const [lpMintAddress] = getLpMintAddress(
+        marketAddress,
+        program.programId
+      );
+      const [protocolFeeVaultAddress] = getProtocolFeeVaultAddress(
+        mint,
+        program.programId
+      );
+      const [creatorFeeVaultAddress] = getCreatorFeeVaultAddress(
+        marketAddress,
+        program.programId
+      );
+
+      const [collateralConfigAddress] = getCollateralConfigAddress(
+        mint,
+        program.programId
+      );
+
       await program.methods
         .initializePools()
         .accountsStrict({
           market: marketAddress,
           marketCreator: hema.publicKey,
           poolTokenMint: mint,
+          collateralConfig: collateralConfigAddress,
           higherPool: higherPoolAddress,
           lowerPool: lowerPoolAddress,
+          lpMint: lpMintAddress,
+          config: configAddress,
+          protocolFeeVault: protocolFeeVaultAddress,
+          creatorFeeVault: creatorFeeVaultAddress,
           userAta: userAta.address,
           systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: token.TOKEN_PROGRAM_ID,
@@ -370,7 +4180,133 @@ describe("prediction_market", () => {
         mint
       );
     });
-    it("Market Canceled", async () => {
+
+    it("Can not slash the bond before the slash deadline", async () => {
+      const [marketAddress] = getMarketAddress(
+        hema.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        program.programId
+      );
+      const market = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(
+        market.creatorBond.toString(),
+        collateralParams.creatorBond.toString()
+      );
+
+      let should_fail = "This Should Fail";
+      try {
+        await program.methods
+          .slashCreatorBond()
+          .accountsStrict({
+            market: marketAddress,
+            config: configAddress,
+            bondVault: getBondVaultAddress(marketAddress, program.programId)[0],
+            protocolFeeVault: getProtocolFeeVaultAddress(
+              market.mint,
+              program.programId
+            )[0],
+            mint: market.mint,
+            slasher: provider.wallet.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            tokenProgram: token.TOKEN_PROGRAM_ID,
+          })
+          .rpc()
+          .then(confirmTx);
+      } catch (e) {
+        const anchorErr = anchor.AnchorError.parse(e.logs);
+        assert.strictEqual(
+          anchorErr.error.errorCode.code,
+          "BondNotSlashable",
+          "Unexpected Error Code"
+        );
+        should_fail = "Failed";
+      }
+      assert.strictEqual(should_fail, "Failed");
+    });
+
+    it("Slashes the bond of a market voided without a price", async () => {
+      const feed = await registerManualFeed("SLH/USD", -8);
+      const marketAddress = await createShortMarket(feed, 140, {
+        settlementWindow: new anchor.BN(10),
+      });
+      const slashDelay = new anchor.BN(5);
+      await program.methods
+        .updateConfig({ ...configParams, bondSlashDelay: slashDelay })
+        .accountsStrict({
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      const market = await program.account.market.fetch(marketAddress);
+      const deadline = market.startTime
+        .add(market.marketDuration)
+        .add(market.settlementWindow);
+      await waitForSlot(deadline.toNumber());
+
+      //no price was published, anyone can void the market
+      await program.methods
+        .voidMarket()
+        .accountsStrict({ market: marketAddress, config: configAddress })
+        .rpc()
+        .then(confirmTx);
+      const voided = await program.account.market.fetch(marketAddress);
+      assert.isTrue(voided.voidedByTimeout);
+
+      const slashAccounts = {
+        market: marketAddress,
+        config: configAddress,
+        bondVault: getBondVaultAddress(marketAddress, program.programId)[0],
+        protocolFeeVault: getProtocolFeeVaultAddress(
+          collateralMint,
+          program.programId
+        )[0],
+        mint: collateralMint,
+        slasher: provider.wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: token.TOKEN_PROGRAM_ID,
+      };
+      await waitForSlot(deadline.add(slashDelay).toNumber());
+      const vaultBefore = await token.getAccount(
+        provider.connection,
+        slashAccounts.protocolFeeVault
+      );
+
+      await program.methods
+        .slashCreatorBond()
+        .accountsStrict(slashAccounts)
+        .rpc()
+        .then(confirmTx);
+
+      await program.methods
+        .updateConfig(configParams)
+        .accountsStrict({
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      const vaultAfter = await token.getAccount(
+        provider.connection,
+        slashAccounts.protocolFeeVault,
+        "confirmed"
+      );
+      assert.strictEqual(
+        (vaultAfter.amount - vaultBefore.amount).toString(),
+        market.creatorBond.toString()
+      );
+      const slashed = await program.account.market.fetch(marketAddress);
+      assert.isFalse(slashed.bondVaultOpen);
+      assert.isNull(
+        await provider.connection.getAccountInfo(slashAccounts.bondVault)
+      );
+    });
+
+    it("Market Finalized", async () => {
       await airdrop(provider.connection, hema.publicKey);
 
       const [marketAddress, marketBump] = getMarketAddress(
@@ -399,21 +4335,66 @@ describe("prediction_market", () => {
         hema.publicKey
       );
 
+      await checkMarket(
+        program,
+        marketAddress,
+        hema.publicKey,
+        feedIdString,
+        targetPrice,
+        marketDuration,
+        marketBump,
+        { initializedPools: {} },
+        higherPoolBump,
+        lowerPoolBump,
+        market.mint
+      );
+
+      const creatorBalanceBefore = Number(creatorAta.amount);
+
+      const [creatorFeeVaultAddress] = getCreatorFeeVaultAddress(
+        marketAddress,
+        program.programId
+      );
+
       await program.methods
-        .cancelMarket()
+        .finalizeMarket()
         .accountsStrict({
           market: marketAddress,
+          lpMint: getLpMintAddress(marketAddress, program.programId)[0],
+          config: configAddress,
           marketCreator: hema.publicKey,
           higherPool: higherPoolAddress,
           lowerPool: lowerPoolAddress,
           creatorAta: creatorAta.address,
+          creatorFeeVault: creatorFeeVaultAddress,
+          creatorRegistry: null,
+          bondVault: getBondVaultAddress(marketAddress, program.programId)[0],
           systemProgram: anchor.web3.SystemProgram.programId,
+          mint: market.mint,
           tokenProgram: token.TOKEN_PROGRAM_ID,
         })
         .signers([hema])
         .rpc()
         .then(confirmTx);
 
+      const creatorAtaAfter = await token.getAccount(
+        provider.connection,
+        creatorAta.address,
+        "confirmed"
+      );
+
+      assert.strictEqual(
+        Number(creatorAtaAfter.amount),
+        creatorBalanceBefore +
+          Number(INITIAL_USDC_AMOUNT) * 2 +
+          collateralParams.creatorBond.toNumber()
+      );
+
+      const bondVault = await provider.connection.getAccountInfo(
+        getBondVaultAddress(marketAddress, program.programId)[0]
+      );
+      assert.isNull(bondVault);
+
       const cancelledMarket = await program.account.market.fetchNullable(
         marketAddress
       );
@@ -430,205 +4411,434 @@ describe("prediction_market", () => {
       assert.isNull(lowerPool);
     });
   });
-  describe("Finalize Market", () => {
-    it("Initializes market again", async () => {
-      await airdrop(provider.connection, hema.publicKey);
 
-      const [marketAddress, marketBump] = getMarketAddress(
-        hema.publicKey,
-        feedIdString,
-        targetPrice,
-        marketDuration,
-        program.programId
-      );
+  //a short market settled by a manual oracle, so the claim can run once it
+  //expires. The minimum market duration is lowered while it is created
+  describe("Claim Bet", () => {
+    const bettor = anchor.web3.Keypair.generate();
+    const oracleAuthority = anchor.web3.Keypair.generate();
+    const shortDuration = new anchor.BN(60);
+    const betAmount = new anchor.BN(10000);
+    const betDirection = { higher: {} };
+    const [oracleAddress] = getManualOracleAddress(
+      oracleAuthority.publicKey,
+      Array.from(
+        crypto.createHash("sha256").update("CLM/USD", "utf-8").digest()
+      ),
+      program.programId
+    );
+    const oracleFeedIdString = "0x" + oracleAddress.toBuffer().toString("hex");
+    const [marketAddress] = getMarketAddress(
+      marketCreator1.publicKey,
+      oracleFeedIdString,
+      targetPrice,
+      shortDuration,
+      program.programId
+    );
+    const [higherPoolAddress] = getPoolAddress(
+      HIGHER_POOL_SEED,
+      marketAddress,
+      program.programId
+    );
+    const [lowerPoolAddress] = getPoolAddress(
+      LOWER_POOL_SEED,
+      marketAddress,
+      program.programId
+    );
+    const [betAddress] = getBetAddress(
+      marketAddress,
+      bettor.publicKey,
+      betAmount,
+      betDirection,
+      program.programId
+    );
+
+    it("Bets and closes the token account before expiry", async () => {
+      await airdrop(provider.connection, bettor.publicKey);
+      await airdrop(provider.connection, oracleAuthority.publicKey);
+      await airdrop(provider.connection, marketCreator1.publicKey);
+      await airdrop(provider.connection, mint_authority.publicKey);
 
       await program.methods
-        .initializeMarket(targetPrice, feedIdString, marketDuration)
+        .initializeManualOracle(
+          Array.from(
+            crypto.createHash("sha256").update("CLM/USD", "utf-8").digest()
+          ),
+          -8
+        )
         .accountsStrict({
-          market: marketAddress,
-          marketCreator: hema.publicKey,
+          manualOracle: oracleAddress,
+          authority: oracleAuthority.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
-        .signers([hema])
+        .signers([oracleAuthority])
         .rpc()
         .then(confirmTx);
 
-      await checkMarket(
-        program,
-        marketAddress,
-        hema.publicKey,
-        feedIdString,
-        targetPrice,
-        marketDuration,
-        marketBump,
-        { initializedMarket: {} }
+      const [oracleFeedRegistryAddress] = getFeedRegistryAddress(
+        oracleFeedIdString,
+        program.programId
       );
-    });
-
-    it("Initialize pool again", async () => {
-      await airdrop(provider.connection, hema.publicKey);
-      await airdrop(provider.connection, mint_authority.publicKey);
+      await program.methods
+        .registerFeed(Array.from(oracleAddress.toBuffer()), {
+          ...feedParams,
+          symbol: "CLM/USD",
+          oracleSource: { manual: {} },
+        })
+        .accountsStrict({
+          feedRegistry: oracleFeedRegistryAddress,
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+          payer: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc()
+        .then(confirmTx);
 
       const mint = await token.createMint(
         provider.connection,
         mint_authority,
         mint_authority.publicKey,
         null,
-        9
+        6
       );
+      await addCollateralMint(program, mint, collateralParams);
 
-      const userAta = await token.getOrCreateAssociatedTokenAccount(
+      const creatorAta = await token.getOrCreateAssociatedTokenAccount(
         provider.connection,
-        hema,
+        marketCreator1,
         mint,
-        hema.publicKey
+        marketCreator1.publicKey
       );
-
       await token.mintTo(
         provider.connection,
         mint_authority,
         mint,
-        userAta.address,
+        creatorAta.address,
         mint_authority,
         to_mint.toNumber()
       );
 
-      const [marketAddress, marketBump] = getMarketAddress(
-        hema.publicKey,
-        feedIdString,
-        targetPrice,
-        marketDuration,
-        program.programId
+      const bettorAta = await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        bettor,
+        mint,
+        bettor.publicKey
       );
-
-      const [higherPoolAddress, higherPoolBump] = getPoolAddress(
-        HIGHER_POOL_SEED,
-        marketAddress,
-        program.programId
+      await token.mintTo(
+        provider.connection,
+        mint_authority,
+        mint,
+        bettorAta.address,
+        mint_authority,
+        betAmount.toNumber()
       );
 
-      const [lowerPoolAddress, lowerPoolBump] = getPoolAddress(
-        LOWER_POOL_SEED,
-        marketAddress,
-        program.programId
-      );
+      await program.methods
+        .updateConfig({ ...configParams, minMarketDuration: shortDuration })
+        .accountsStrict({
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      await program.methods
+        .initializeMarket(
+          targetPrice,
+          Buffer.from(oracleFeedIdString),
+          shortDuration,
+          { ...marketParams, feeCurve: null }
+        )
+        .accountsStrict({
+          marketCreator: marketCreator1.publicKey,
+          market: marketAddress,
+          config: configAddress,
+          feedRegistry: oracleFeedRegistryAddress,
+          creatorRegistry: null,
+          ...getCreatorBondAccounts(
+            marketAddress,
+            marketCreator1.publicKey,
+            mint,
+            program.programId
+          ),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
+
+      await program.methods
+        .initializePools()
+        .accountsStrict({
+          market: marketAddress,
+          marketCreator: marketCreator1.publicKey,
+          poolTokenMint: mint,
+          collateralConfig: getCollateralConfigAddress(
+            mint,
+            program.programId
+          )[0],
+          higherPool: higherPoolAddress,
+          lowerPool: lowerPoolAddress,
+          lpMint: getLpMintAddress(marketAddress, program.programId)[0],
+          config: configAddress,
+          protocolFeeVault: getProtocolFeeVaultAddress(
+            mint,
+            program.programId
+          )[0],
+          creatorFeeVault: getCreatorFeeVaultAddress(
+            marketAddress,
+            program.programId
+          )[0],
+          userAta: creatorAta.address,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([marketCreator1])
+        .rpc()
+        .then(confirmTx);
 
       await program.methods
-        .initializePools()
+        .placeBet(betAmount, betDirection)
         .accountsStrict({
+          bet: betAddress,
           market: marketAddress,
-          marketCreator: hema.publicKey,
-          poolTokenMint: mint,
+          config: configAddress,
+          protocolFeeVault: getProtocolFeeVaultAddress(
+            mint,
+            program.programId
+          )[0],
+          creatorFeeVault: getCreatorFeeVaultAddress(
+            marketAddress,
+            program.programId
+          )[0],
+          referrerStats: null,
+          referralVault: null,
+          user: bettor.publicKey,
           higherPool: higherPoolAddress,
           lowerPool: lowerPoolAddress,
-          userAta: userAta.address,
+          userAta: bettorAta.address,
           systemProgram: anchor.web3.SystemProgram.programId,
+          mint,
           tokenProgram: token.TOKEN_PROGRAM_ID,
         })
-        .signers([hema])
+        .signers([bettor])
         .rpc()
         .then(confirmTx);
 
-      await checkMarket(
-        program,
-        marketAddress,
-        hema.publicKey,
-        feedIdString,
-        targetPrice,
-        marketDuration,
-        marketBump,
-        { initializedPools: {} },
-        higherPoolBump,
-        lowerPoolBump,
-        mint
+      await program.methods
+        .updateConfig(configParams)
+        .accountsStrict({
+          config: configAddress,
+          admin: provider.wallet.publicKey,
+        })
+        .rpc()
+        .then(confirmTx);
+
+      await token.closeAccount(
+        provider.connection,
+        bettor,
+        bettorAta.address,
+        bettor.publicKey,
+        bettor
       );
+      const closedAta = await provider.connection.getAccountInfo(
+        bettorAta.address
+      );
+      assert.isNull(closedAta);
     });
 
-    it("Market Finalized", async () => {
-      await airdrop(provider.connection, hema.publicKey);
-
-      const [marketAddress, marketBump] = getMarketAddress(
-        hema.publicKey,
-        feedIdString,
-        targetPrice,
-        marketDuration,
-        program.programId
-      );
+    it("Claims into a re-created token account", async () => {
       const market = await program.account.market.fetch(marketAddress);
+      const expiry = market.startTime.add(market.marketDuration).toNumber();
+      while ((await provider.connection.getSlot("confirmed")) <= expiry) {
+        await new Promise((resolve) => setTimeout(resolve, 400));
+      }
+      //the price must be published after the expiry in unix time
+      await new Promise((resolve) => setTimeout(resolve, 2000));
 
-      const [higherPoolAddress, higherPoolBump] = getPoolAddress(
-        HIGHER_POOL_SEED,
-        marketAddress,
-        program.programId
-      );
-      const [lowerPoolAddress, lowerPoolBump] = getPoolAddress(
-        LOWER_POOL_SEED,
-        marketAddress,
-        program.programId
-      );
-      const creatorAta = await token.getOrCreateAssociatedTokenAccount(
-        provider.connection,
-        hema,
-        market.mint,
-        hema.publicKey
-      );
+      await program.methods
+        .updateManualOracle(new anchor.BN(15_000_000_000), new anchor.BN(0))
+        .accountsStrict({
+          manualOracle: oracleAddress,
+          authority: oracleAuthority.publicKey,
+        })
+        .signers([oracleAuthority])
+        .rpc()
+        .then(confirmTx);
 
-      await checkMarket(
-        program,
-        marketAddress,
-        hema.publicKey,
-        feedIdString,
-        targetPrice,
-        marketDuration,
-        marketBump,
-        { initializedPools: {} },
-        higherPoolBump,
-        lowerPoolBump,
-        market.mint
+      const bet = await program.account.bet.fetch(betAddress);
+      const payout = bet.stake.mul(bet.odds).divn(1_000_000);
+      const bettorAta = token.getAssociatedTokenAddressSync(
+        market.mint,
+        bettor.publicKey
       );
 
-      const creatorBalanceBefore = Number(creatorAta.amount);
-
       await program.methods
-        .finalizeMarket()
+        .claimBet()
         .accountsStrict({
           market: marketAddress,
-          marketCreator: hema.publicKey,
+          config: configAddress,
           higherPool: higherPoolAddress,
           lowerPool: lowerPoolAddress,
-          creatorAta: creatorAta.address,
+          userAta: bettorAta,
+          unwrapAccount: null,
+          user: bettor.publicKey,
+          bet: betAddress,
+          priceUpdate: oracleAddress,
+          mint: market.mint,
           systemProgram: anchor.web3.SystemProgram.programId,
           tokenProgram: token.TOKEN_PROGRAM_ID,
+          associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
         })
-        .signers([hema])
+        .signers([bettor])
         .rpc()
         .then(confirmTx);
 
-      const creatorAtaAfter = await token.getAccount(
+      const ata = await token.getAccount(provider.connection, bettorAta);
+      assert.strictEqual(ata.amount.toString(), payout.toString());
+
+      const settled = await program.account.market.fetch(marketAddress);
+      assert.strictEqual(settled.finalPrice.toString(), "150");
+    });
+  });
+
+  //accounts written by the program before the upgrade, preloaded from
+  //tests/fixtures: a settled market with pools and an unclaimed winning bet
+  describe("Legacy Migration", () => {
+    const legacyCreator = loadKeypair("tests/fixtures/legacy-creator.json");
+    const legacyBettor = loadKeypair("tests/fixtures/legacy-bettor.json");
+    const legacyMint = new PublicKey(
+      "3aJw9D7D81yYEwGR3ZH1T9JWpjneEgCvXg5ijyiwnxbG"
+    );
+    const legacyBetAmount = new anchor.BN(1_000_000);
+    const legacyOdds = 2;
+    const [legacyMarketAddress] = PublicKey.findProgramAddressSync(
+      [
+        legacyCreator.publicKey.toBuffer(),
+        crypto.createHash("sha256").update(feedIdString).digest(),
+        targetPrice.toArrayLike(Buffer, "le", 8),
+        new anchor.BN(1).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [legacyBetAddress] = getBetAddress(
+      legacyMarketAddress,
+      legacyBettor.publicKey,
+      legacyBetAmount,
+      { higher: {} },
+      program.programId
+    );
+
+    it("Migrates a legacy market and creates its vaults", async () => {
+      await airdrop(provider.connection, legacyCreator.publicKey);
+
+      await program.methods
+        .migrateLegacyMarket()
+        .accountsStrict({
+          market: legacyMarketAddress,
+          lpMint: getLpMintAddress(legacyMarketAddress, program.programId)[0],
+          creatorFeeVault: getCreatorFeeVaultAddress(
+            legacyMarketAddress,
+            program.programId
+          )[0],
+          config: configAddress,
+          mint: legacyMint,
+          marketCreator: legacyCreator.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([legacyCreator])
+        .rpc()
+        .then(confirmTx);
+
+      const market = await program.account.market.fetch(legacyMarketAddress);
+      assert.deepEqual(market.feedVersion, { legacyHex: {} });
+      //the seed of the old program, whatever the config seeds today
+      assert.strictEqual(market.seedAmount.toString(), "1000000");
+      assert.notStrictEqual(market.lpMintBump, 0);
+      assert.notStrictEqual(market.creatorFeeVaultBump, 0);
+
+      const lpMint = await token.getMint(
         provider.connection,
-        creatorAta.address,
-        "confirmed"
+        getLpMintAddress(legacyMarketAddress, program.programId)[0]
       );
+      assert.strictEqual(Number(lpMint.supply), 0);
+    });
+
+    it("Migrates a legacy bet and reserves its payout", async () => {
+      await airdrop(provider.connection, legacyBettor.publicKey);
+
+      await program.methods
+        .migrateLegacyBet()
+        .accountsStrict({
+          market: legacyMarketAddress,
+          bet: legacyBetAddress,
+          payer: legacyBettor.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([legacyBettor])
+        .rpc()
+        .then(confirmTx);
+
+      const bet = await program.account.bet.fetch(legacyBetAddress);
+      assert.strictEqual(bet.stake.toString(), legacyBetAmount.toString());
+      assert.strictEqual(bet.protocolFee.toNumber(), 0);
 
+      const market = await program.account.market.fetch(legacyMarketAddress);
       assert.strictEqual(
-        Number(creatorAtaAfter.amount),
-        creatorBalanceBefore + Number(INITIAL_USDC_AMOUNT) * 2
+        market.higherStake.toString(),
+        legacyBetAmount.toString()
       );
-
-      const cancelledMarket = await program.account.market.fetchNullable(
-        marketAddress
+      assert.strictEqual(
+        market.higherLiability.toString(),
+        legacyBetAmount.muln(legacyOdds).toString()
       );
-      assert.isNull(cancelledMarket);
+    });
 
-      const higherPool = await program.account.market.fetchNullable(
-        higherPoolAddress
+    it("Claims a bet placed before the migration", async () => {
+      const userAta = token.getAssociatedTokenAddressSync(
+        legacyMint,
+        legacyBettor.publicKey
       );
-      assert.isNull(higherPool);
 
-      const lowerPool = await program.account.market.fetchNullable(
-        lowerPoolAddress
+      await program.methods
+        .claimBet()
+        .accountsStrict({
+          market: legacyMarketAddress,
+          config: configAddress,
+          higherPool: getPoolAddress(
+            HIGHER_POOL_SEED,
+            legacyMarketAddress,
+            program.programId
+          )[0],
+          lowerPool: getPoolAddress(
+            LOWER_POOL_SEED,
+            legacyMarketAddress,
+            program.programId
+          )[0],
+          userAta,
+          unwrapAccount: null,
+          user: legacyBettor.publicKey,
+          bet: legacyBetAddress,
+          priceUpdate: anchor.web3.SystemProgram.programId,
+          mint: legacyMint,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+          associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([legacyBettor])
+        .rpc()
+        .then(confirmTx);
+
+      const ata = await token.getAccount(provider.connection, userAta);
+      assert.strictEqual(
+        ata.amount.toString(),
+        legacyBetAmount.muln(legacyOdds).toString()
       );
-      assert.isNull(lowerPool);
+
+      const bet = await program.account.bet.fetchNullable(legacyBetAddress);
+      assert.isNull(bet);
     });
   });
 });
@@ -641,6 +4851,12 @@ async function airdrop(
   await connection.requestAirdrop(address, amount).then(confirmTx);
 }
 
+function loadKeypair(path: string) {
+  return anchor.web3.Keypair.fromSecretKey(
+    Uint8Array.from(JSON.parse(fs.readFileSync(path, "utf8")))
+  );
+}
+
 function getMarketAddress(
   creator: PublicKey,
   feedId: string,
@@ -648,11 +4864,7 @@ function getMarketAddress(
   marketDuration: anchor.BN,
   programID: PublicKey
 ) {
-  let hexString = crypto
-    .createHash("sha256")
-    .update(feedId, "utf-8")
-    .digest("hex");
-  let feed_seed = Uint8Array.from(Buffer.from(hexString, "hex"));
+  const feed_seed = feedIdToBytes(feedId);
 
   return PublicKey.findProgramAddressSync(
     [
@@ -676,6 +4888,184 @@ function getPoolAddress(
   );
 }
 
+function getConfigAddress(programId: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(CONFIG_SEED)],
+    programId
+  );
+}
+
+function getProtocolFeeVaultAddress(mint: PublicKey, programId: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(PROTOCOL_FEE_VAULT_SEED), mint.toBuffer()],
+    programId
+  );
+}
+
+function getBondVaultAddress(marketAddress: PublicKey, programId: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(BOND_VAULT_SEED),
+      marketAddress.toBuffer(),
+    ],
+    programId
+  );
+}
+
+function getCreatorFeeVaultAddress(
+  marketAddress: PublicKey,
+  programId: PublicKey
+) {
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(CREATOR_FEE_VAULT_SEED),
+      marketAddress.toBuffer(),
+    ],
+    programId
+  );
+}
+
+function getReferrerStatsAddress(
+  referrer: PublicKey,
+  mint: PublicKey,
+  programId: PublicKey
+) {
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(REFERRER_SEED),
+      referrer.toBuffer(),
+      mint.toBuffer(),
+    ],
+    programId
+  );
+}
+
+function getReferralVaultAddress(mint: PublicKey, programId: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(REFERRAL_VAULT_SEED), mint.toBuffer()],
+    programId
+  );
+}
+
+function getFeedRegistryAddress(feedId: string, programId: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(FEED_REGISTRY_SEED),
+      feedIdToBytes(feedId),
+    ],
+    programId
+  );
+}
+
+function getEventMarketAddress(
+  creator: PublicKey,
+  eventId: number[],
+  marketDuration: anchor.BN,
+  programID: PublicKey
+) {
+  return PublicKey.findProgramAddressSync(
+    [
+      creator.toBuffer(),
+      Buffer.from(eventId),
+      new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+      marketDuration.toArrayLike(Buffer, "le", 8),
+    ],
+    programID
+  );
+}
+
+function getResolutionAddress(
+  seed: string,
+  marketAddress: PublicKey,
+  programId: PublicKey
+) {
+  return PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(seed), marketAddress.toBuffer()],
+    programId
+  );
+}
+
+function getManualOracleAddress(
+  authority: PublicKey,
+  feedId: number[],
+  programId: PublicKey
+) {
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(MANUAL_ORACLE_SEED),
+      authority.toBuffer(),
+      Buffer.from(feedId),
+    ],
+    programId
+  );
+}
+
+function getCreatorRegistryAddress(creator: PublicKey, programId: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(CREATOR_REGISTRY_SEED),
+      creator.toBuffer(),
+    ],
+    programId
+  );
+}
+
+function getCollateralConfigAddress(mint: PublicKey, programId: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(COLLATERAL_CONFIG_SEED), mint.toBuffer()],
+    programId
+  );
+}
+
+async function addCollateralMint(
+  program: Program<PredictionMarket>,
+  mint: PublicKey,
+  params: { minBet: anchor.BN; maxBet: anchor.BN; creatorBond: anchor.BN }
+) {
+  await program.methods
+    .addCollateralMint(params)
+    .accountsStrict({
+      collateralConfig: getCollateralConfigAddress(mint, program.programId)[0],
+      config: getConfigAddress(program.programId)[0],
+      mint,
+      admin: program.provider.publicKey,
+      payer: program.provider.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc()
+    .then(confirmTx);
+}
+
+// Accounts through which initializeMarket and initializeEventMarket post the
+// creator bond in `mint`
+function getCreatorBondAccounts(
+  marketAddress: PublicKey,
+  creator: PublicKey,
+  mint: PublicKey,
+  programId: PublicKey,
+  tokenProgram = token.TOKEN_PROGRAM_ID
+) {
+  return {
+    mint,
+    collateralConfig: getCollateralConfigAddress(mint, programId)[0],
+    bondVault: getBondVaultAddress(marketAddress, programId)[0],
+    creatorAta: token.getAssociatedTokenAddressSync(
+      mint,
+      creator,
+      false,
+      tokenProgram
+    ),
+    tokenProgram,
+  };
+}
+
+function getLpMintAddress(marketAddress: PublicKey, programId: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(LP_MINT_SEED), marketAddress.toBuffer()],
+    programId
+  );
+}
+
 function getBetAddress(
   marketAddress: PublicKey,
   userAddress: PublicKey,
@@ -723,14 +5113,9 @@ async function checkMarket(
   );
   assert.strictEqual(marketData.bump.toString(), bump.toString());
 
-  const utf8ByteArray_content = stringToUtf8ByteArray(feedId);
-  const paddedByteArray_content = padByteArrayWithZeroes(
-    utf8ByteArray_content,
-    66
-  );
   assert.strictEqual(
     marketData.feedId.toString(),
-    paddedByteArray_content.toString()
+    Array.from(feedIdToBytes(feedId)).toString()
   );
 
   assert.strictEqual(
@@ -756,22 +5141,65 @@ async function checkMarket(
   }
 }
 
-function stringToUtf8ByteArray(inputString: string): Uint8Array {
-  const encoder = new TextEncoder();
-  return encoder.encode(inputString);
+// Creates a 6 decimal token-2022 mint with a 1% transfer fee or a permanent
+// delegate, `authority` holds every authority of the mint
+async function createToken2022Mint(
+  connection: anchor.web3.Connection,
+  authority: anchor.web3.Keypair,
+  extension: token.ExtensionType
+): Promise<PublicKey> {
+  const mint = anchor.web3.Keypair.generate();
+  const mintLen = token.getMintLen([extension]);
+  const lamports = await connection.getMinimumBalanceForRentExemption(mintLen);
+
+  const initializeExtension =
+    extension == token.ExtensionType.TransferFeeConfig
+      ? token.createInitializeTransferFeeConfigInstruction(
+          mint.publicKey,
+          authority.publicKey,
+          authority.publicKey,
+          100,
+          BigInt(1_000_000_000),
+          token.TOKEN_2022_PROGRAM_ID
+        )
+      : token.createInitializePermanentDelegateInstruction(
+          mint.publicKey,
+          authority.publicKey,
+          token.TOKEN_2022_PROGRAM_ID
+        );
+
+  const tx = new anchor.web3.Transaction().add(
+    anchor.web3.SystemProgram.createAccount({
+      fromPubkey: authority.publicKey,
+      newAccountPubkey: mint.publicKey,
+      space: mintLen,
+      lamports,
+      programId: token.TOKEN_2022_PROGRAM_ID,
+    }),
+    initializeExtension,
+    token.createInitializeMintInstruction(
+      mint.publicKey,
+      6,
+      authority.publicKey,
+      null,
+      token.TOKEN_2022_PROGRAM_ID
+    )
+  );
+  await anchor.web3.sendAndConfirmTransaction(connection, tx, [
+    authority,
+    mint,
+  ]);
+
+  return mint.publicKey;
 }
 
-// Function to pad a byte array with zeroes to a specified length
-function padByteArrayWithZeroes(
-  byteArray: Uint8Array,
-  length: number
-): Uint8Array {
-  if (byteArray.length >= length) {
-    return byteArray;
+// Feed ids are stored as 32 bytes, unparseable ids map to the zero seed
+function feedIdToBytes(feedId: string): Buffer {
+  const hex = feedId.startsWith("0x") ? feedId.slice(2) : feedId;
+  if (/^[0-9a-fA-F]{64}$/.test(hex)) {
+    return Buffer.from(hex, "hex");
   }
-  const paddedArray = new Uint8Array(length);
-  paddedArray.set(byteArray, 0);
-  return paddedArray;
+  return Buffer.alloc(32);
 }
 
 const confirmTx = async (signature: string) => {